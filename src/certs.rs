@@ -0,0 +1,229 @@
+use openssl::{
+    asn1::Asn1Time, pkcs12::Pkcs12, pkey::{PKey, Private}, x509::{X509, X509NameRef}
+};
+
+use crate::config::{Config, ConfigError, Protocol};
+
+// location displayed for certificates provided as inline PEM
+const INLINE_PEM: &str = "inline PEM";
+
+// Details of a single certificate read from one of the configured PEM files
+#[derive(Debug, Clone)]
+pub struct CertificateInfo {
+    pub label: String,
+    pub location: String,
+    pub subject: String,
+    pub issuer: String,
+    pub sans: Vec<String>,
+    pub not_after: String,
+    pub days_to_expiry: i32,
+}
+
+impl CertificateInfo {
+    fn new(label: &str, location: &str, cert: &X509) -> Result<CertificateInfo, ConfigError> {
+        let days_to_expiry = Asn1Time::days_from_now(0)
+            .and_then(|now| now.diff(cert.not_after()))
+            .map_err(|err| ConfigError::new(&format!("unable to calculate expiry of certificate {}: {}", location, err)))?
+            .days;
+
+        Ok(CertificateInfo {
+            label: label.to_string(),
+            location: location.to_string(),
+            subject: format_name(cert.subject_name()),
+            issuer: format_name(cert.issuer_name()),
+            sans: subject_alt_names(cert),
+            not_after: cert.not_after().to_string(),
+            days_to_expiry,
+        })
+    }
+
+    // Human readable expiry, e.g. "in 12 days" or "3 days ago"
+    pub fn expiry(&self) -> String {
+        if self.days_to_expiry < 0 {
+            format!("{} days ago", -self.days_to_expiry)
+        } else {
+            format!("in {} days", self.days_to_expiry)
+        }
+    }
+}
+
+// Connection details displayed in the connection info panel
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    pub bootstrap_servers: String,
+    pub protocol: String,
+    pub certificates: Vec<CertificateInfo>,
+    expiry_warning_days: i32,
+}
+
+impl ConnectionInfo {
+    // Whether the certificate expires within the configured number of days
+    pub fn expiry_warning(&self, cert: &CertificateInfo) -> bool {
+        cert.days_to_expiry <= self.expiry_warning_days
+    }
+
+    // Warnings for all the certificates expiring within the configured number of days
+    pub fn expiry_warnings(&self) -> Vec<String> {
+        self.certificates.iter()
+            .filter(|c| self.expiry_warning(c))
+            .map(|c| format!("{} {} expires {}", c.label, common_name(&c.subject), c.expiry()))
+            .collect()
+    }
+}
+
+// Inspect the SSL files, keystore & inline PEMs provided in the config
+// fails if any of the files cannot be parsed or the client key does not match the client certificate
+pub fn inspect(config: &Config) -> Result<ConnectionInfo, ConfigError> {
+    let mut connection_info = ConnectionInfo {
+        bootstrap_servers: config.bootstrap_servers.clone(),
+        protocol: config.protocol.to_string(),
+        certificates: vec![],
+        expiry_warning_days: config.cert_expiry_warning_days,
+    };
+
+    // SSL files are only used with SSL based protocols
+    match config.protocol {
+        Protocol::Ssl | Protocol::SaslSsl => (),
+        _ => return Ok(connection_info),
+    }
+
+    // CA can be a bundle of multiple certificates
+    if let Some((ca_location, ca_pem)) = pem_source(&config.ssl_ca_location, &config.ssl_ca_pem)? {
+        for cert in X509::stack_from_pem(&ca_pem)
+            .map_err(|err| ConfigError::new(&format!("unable to parse CA certificates from {}: {}", ca_location, err)))? {
+            connection_info.certificates.push(CertificateInfo::new("CA certificate", &ca_location, &cert)?);
+        }
+    }
+
+    // keystore contains client key, certificate and optionally the CA chain
+    if let Some(keystore_location) = &config.ssl_keystore_location {
+        let keystore = Pkcs12::from_der(&read_file(keystore_location)?)
+            .and_then(|p| p.parse2(config.ssl_keystore_password.as_deref().unwrap_or("")))
+            .map_err(|err| ConfigError::new(&format!("unable to open keystore {}, check the keystore password: {}", keystore_location, err)))?;
+
+        let cert = keystore.cert
+            .ok_or(ConfigError::new(&format!("no client certificate found in keystore {}", keystore_location)))?;
+        let key = keystore.pkey
+            .ok_or(ConfigError::new(&format!("no client key found in keystore {}", keystore_location)))?;
+
+        check_key_matches(&cert, &key, keystore_location, keystore_location)?;
+        connection_info.certificates.push(CertificateInfo::new("Client certificate", keystore_location, &cert)?);
+
+        if let Some(ca_certs) = keystore.ca {
+            for cert in ca_certs.iter() {
+                connection_info.certificates.push(CertificateInfo::new("CA certificate", keystore_location, &cert.to_owned())?);
+            }
+        }
+    }
+
+    if let Some((cert_location, cert_pem)) = pem_source(&config.ssl_client_certificate_location, &config.ssl_client_certificate_pem)? {
+        let cert = X509::from_pem(&cert_pem)
+            .map_err(|err| ConfigError::new(&format!("unable to parse client certificate {}: {}", cert_location, err)))?;
+
+        // check if the client key belongs to the client certificate
+        if let Some((key_location, key_pem)) = pem_source(&config.ssl_client_key_location, &config.ssl_client_key_pem)? {
+            // without the password OpenSSL would prompt for it on the terminal
+            let key = match &config.ssl_client_key_password {
+                Some(password) => Some(PKey::private_key_from_pem_passphrase(&key_pem, password.as_bytes())),
+                None if is_encrypted(&key_pem) => {
+                    log::warn!("client key {} is encrypted & no password is given, skipping the check of the key against the client certificate", key_location);
+                    None
+                },
+                None => Some(PKey::private_key_from_pem(&key_pem)),
+            };
+
+            if let Some(key) = key {
+                let key = key.map_err(|err| ConfigError::new(&format!("unable to parse client key {}, check the key password: {}", key_location, err)))?;
+                check_key_matches(&cert, &key, &key_location, &cert_location)?;
+            }
+        }
+
+        connection_info.certificates.push(CertificateInfo::new("Client certificate", &cert_location, &cert)?);
+    }
+
+    Ok(connection_info)
+}
+
+fn check_key_matches(cert: &X509, key: &PKey<Private>, key_location: &str, cert_location: &str) -> Result<(), ConfigError> {
+    let matches = cert.public_key()
+        .map(|public_key| public_key.public_eq(key))
+        .unwrap_or(false);
+
+    if !matches {
+        return Err(ConfigError::new(&format!("client key {} does not match client certificate {}", key_location, cert_location)));
+    }
+
+    Ok(())
+}
+
+// Encrypted PEMs are either PKCS#8 (ENCRYPTED PRIVATE KEY) or legacy PEMs with a Proc-Type: 4,ENCRYPTED header
+fn is_encrypted(pem: &[u8]) -> bool {
+    String::from_utf8_lossy(pem).contains("ENCRYPTED")
+}
+
+// Returns the location and content of a PEM provided either as a file or inline
+fn pem_source(location: &Option<String>, pem: &Option<String>) -> Result<Option<(String, Vec<u8>)>, ConfigError> {
+    if let Some(location) = location {
+        return Ok(Some((location.clone(), read_file(location)?)));
+    }
+
+    Ok(pem.as_ref().map(|p| (INLINE_PEM.to_string(), p.as_bytes().to_vec())))
+}
+
+fn read_file(location: &str) -> Result<Vec<u8>, ConfigError> {
+    std::fs::read(location)
+        .map_err(|err| ConfigError::new(&format!("unable to read {}: {}", location, err)))
+}
+
+// Format X509 name as a comma separated list, e.g. "CN=kafka, O=org"
+fn format_name(name: &X509NameRef) -> String {
+    name.entries()
+        .map(|e| {
+            let key = e.object().nid().short_name().unwrap_or("?");
+            let value = e.data().as_utf8()
+                .map(|v| v.to_string())
+                .unwrap_or_default();
+            format!("{}={}", key, value)
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// Collect DNS, IP, email & URI subject alternative names
+fn subject_alt_names(cert: &X509) -> Vec<String> {
+    let mut sans = vec![];
+    if let Some(names) = cert.subject_alt_names() {
+        for name in names.iter() {
+            if let Some(dns) = name.dnsname() {
+                sans.push(format!("DNS:{}", dns));
+            } else if let Some(ip) = name.ipaddress() {
+                sans.push(format!("IP:{}", format_ip(ip)));
+            } else if let Some(email) = name.email() {
+                sans.push(format!("email:{}", email));
+            } else if let Some(uri) = name.uri() {
+                sans.push(format!("URI:{}", uri));
+            }
+        }
+    }
+
+    sans
+}
+
+fn format_ip(ip: &[u8]) -> String {
+    match ip.len() {
+        4 => std::net::Ipv4Addr::new(ip[0], ip[1], ip[2], ip[3]).to_string(),
+        16 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(ip);
+            std::net::Ipv6Addr::from(octets).to_string()
+        },
+        _ => format!("{:?}", ip),
+    }
+}
+
+// Common name of the certificate, falls back to the full subject
+fn common_name(subject: &str) -> &str {
+    subject.split(", ")
+        .find_map(|e| e.strip_prefix("CN="))
+        .unwrap_or(subject)
+}
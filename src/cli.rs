@@ -0,0 +1,853 @@
+use std::{collections::{HashMap, VecDeque}, fmt::Display, fs::File, io::{BufRead, BufReader, BufWriter, ErrorKind, Write}, path::PathBuf, time::{Duration, Instant}};
+
+use rdkafka::{producer::DeliveryFuture, ClientConfig, Offset, TopicPartitionList};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::{Acks, CliCommand, Compression, GroupsCommand, OutputFormat, TopicsCommand, ACKS, BATCH_NUM_MESSAGES, COMPRESSION_TYPE, GROUP_ID, LINGER_MS};
+use crate::kafka::consumer::{Consumer, ConsumerError, DefaultContext, KafkaMessage, OffsetBound};
+use crate::kafka::producer::{await_delivery, Producer, ProducerError, ProducerRecord};
+use crate::kafka::metadata::{is_internal_topic, Metadata};
+use crate::tui::command::{parse_timestamp, CommandError};
+
+// Exit codes of the subcommands
+pub const EXIT_SUCCESS: i32 = 0;
+// cluster is unreachable or a request failed
+pub const EXIT_FAILURE: i32 = 1;
+// args which could not be validated while parsing, e.g. timestamps
+pub const EXIT_INVALID_ARGS: i32 = 2;
+// topic or consumer group does not exist
+pub const EXIT_NOT_FOUND: i32 = 3;
+
+// Error of a subcommand along with the exit code it results in
+#[derive(Debug)]
+struct CliError {
+    message: String,
+    code: i32,
+}
+
+impl CliError {
+    fn not_found(message: String) -> CliError {
+        CliError { message, code: EXIT_NOT_FOUND }
+    }
+}
+
+// Messages are polled for this long before checking whether the consumer reached the end of the range
+const CONSUME_POLL_TIMEOUT: Duration = Duration::from_secs(1);
+
+// Range of the messages to consume, offsets & timestamps are resolved once the partition is assigned
+struct ConsumeRange {
+    from_offset: Option<i64>,
+    from_timestamp: Option<String>,
+    to_offset: Option<i64>,
+    to_timestamp: Option<String>,
+    count: Option<usize>,
+    follow: bool,
+}
+
+// Deliveries awaited at most, older deliveries are awaited before producing more records
+const MAX_PENDING_DELIVERIES: usize = 10000;
+
+// Records to produce & how they are produced
+struct ProduceOptions {
+    input: Option<PathBuf>,
+    jsonl: bool,
+    partition: Option<i32>,
+    acks: Acks,
+    compression: Compression,
+    linger_ms: u64,
+    batch_size: u64,
+    rate: Option<u32>,
+}
+
+// Line of the JSONL input, a value which is not a string is produced as JSON
+#[derive(Deserialize)]
+struct JsonRecord {
+    key: Option<String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    value: Option<Value>,
+    partition: Option<i32>,
+}
+
+#[derive(Serialize)]
+struct ProduceSummary {
+    topic: String,
+    produced: usize,
+    failed: usize,
+}
+
+impl From<ConsumerError> for CliError {
+    fn from(value: ConsumerError) -> Self {
+        CliError { message: value.to_string(), code: EXIT_FAILURE }
+    }
+}
+
+impl From<ProducerError> for CliError {
+    fn from(value: ProducerError) -> Self {
+        CliError { message: value.to_string(), code: EXIT_FAILURE }
+    }
+}
+
+#[derive(Serialize)]
+struct BrokerSummary {
+    id: i32,
+    name: String,
+    leader_of: usize,
+    replica_of: usize,
+}
+
+#[derive(Serialize)]
+struct TopicSummary {
+    name: String,
+    internal: bool,
+    partitions: usize,
+    replication_factor: usize,
+    under_replicated: usize,
+    offline: usize,
+}
+
+#[derive(Serialize)]
+struct TopicDetails {
+    name: String,
+    internal: bool,
+    messages: i64,
+    partitions: Vec<PartitionDetails>,
+}
+
+#[derive(Serialize)]
+struct PartitionDetails {
+    id: i32,
+    leader: i32,
+    replicas: Vec<i32>,
+    isr: Vec<i32>,
+    // watermarks of offline partitions are unknown
+    low_watermark: Option<i64>,
+    high_watermark: Option<i64>,
+    messages: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct GroupSummary {
+    name: String,
+    state: String,
+    protocol_type: String,
+    protocol: String,
+    members: usize,
+}
+
+#[derive(Serialize)]
+struct GroupDetails {
+    name: String,
+    state: String,
+    protocol_type: String,
+    protocol: String,
+    members: Vec<MemberDetails>,
+    // lag summed across the partitions with committed offsets
+    lag: i64,
+    offsets: Vec<OffsetDetails>,
+}
+
+#[derive(Serialize)]
+struct MemberDetails {
+    id: String,
+    client_id: String,
+    client_host: String,
+}
+
+#[derive(Serialize)]
+struct OffsetDetails {
+    topic: String,
+    partition: i32,
+    committed: i64,
+    high_watermark: i64,
+    lag: i64,
+}
+
+// Run the subcommand & print its output, returns the exit code
+// errors are printed to stderr so that the output can be piped
+pub fn run(command: CliCommand, output: OutputFormat, read_only: bool, client_config: &ClientConfig, context: DefaultContext) -> i32 {
+    if read_only && command.is_write() {
+        eprintln!("error: subcommand is not allowed in read-only mode");
+        return EXIT_FAILURE;
+    }
+
+    let result = match command {
+        // messages are printed as they are consumed instead of once the subcommand completes
+        CliCommand::Consume { topic, partition, from_offset, from_timestamp, to_offset, to_timestamp, format, count, follow } => {
+            let range = ConsumeRange { from_offset, from_timestamp, to_offset, to_timestamp, count, follow };
+            consume(&topic, partition, range, output, &format, client_config, context)
+        },
+        CliCommand::Produce { topic, input, jsonl, partition, acks, compression, linger_ms, batch_size, rate } => {
+            let options = ProduceOptions { input, jsonl, partition, acks, compression, linger_ms, batch_size, rate };
+            produce(&topic, options, output, client_config, context)
+        },
+        CliCommand::Brokers => execute(client_config, context, |_, metadata| brokers_output(metadata, output)),
+        CliCommand::Topics { command: TopicsCommand::List } => execute(client_config, context, |_, metadata| topics_output(metadata, output)),
+        CliCommand::Topics { command: TopicsCommand::Describe { topic } } => execute(client_config, context,
+            |consumer, metadata| topic_output(consumer, metadata, &topic, output)),
+        CliCommand::Groups { command: GroupsCommand::List } => execute(client_config, context, |_, metadata| groups_output(metadata, output)),
+        CliCommand::Groups { command: GroupsCommand::Describe { group } } => execute(client_config, context.clone(),
+            |consumer, metadata| group_output(consumer, metadata, &group, output, client_config, context)),
+    };
+
+    match result {
+        Ok(_) => EXIT_SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err.message);
+            err.code
+        }
+    }
+}
+
+// Print the output of the subcommand describing the cluster
+fn execute(client_config: &ClientConfig, context: DefaultContext,
+    describe: impl FnOnce(&Consumer<DefaultContext>, &Metadata) -> Result<String, CliError>) -> Result<(), CliError> {
+    // subcommands never write to the cluster
+    let mut consumer = Consumer::new(client_config, context, true)?;
+    let metadata = consumer.fetch_metadata()?;
+    let consumer_groups = consumer.fetch_groups()?;
+    consumer.update_metadata(metadata, consumer_groups);
+    let metadata = consumer.metadata().clone();
+
+    println!("{}", describe(&consumer, &metadata)?);
+    Ok(())
+}
+
+fn brokers_output(metadata: &Metadata, output: OutputFormat) -> Result<String, CliError> {
+    let brokers = brokers(metadata);
+    format(output, &brokers, || table(&["ID", "BROKER", "LEADER OF", "REPLICA OF"], brokers.iter()
+        .map(|b| vec![b.id.to_string(), b.name.clone(), b.leader_of.to_string(), b.replica_of.to_string()])
+        .collect()))
+}
+
+fn topics_output(metadata: &Metadata, output: OutputFormat) -> Result<String, CliError> {
+    let topics = topics(metadata);
+    format(output, &topics, || table(&["TOPIC", "PARTITIONS", "REPLICATION", "UNDER-REPLICATED", "OFFLINE"], topics.iter()
+        .map(|t| vec![t.name.clone(), t.partitions.to_string(), t.replication_factor.to_string(), t.under_replicated.to_string(), t.offline.to_string()])
+        .collect()))
+}
+
+fn topic_output(consumer: &Consumer<DefaultContext>, metadata: &Metadata, topic: &str, output: OutputFormat) -> Result<String, CliError> {
+    let topic = describe_topic(consumer, metadata, topic)?;
+    format(output, &topic, || {
+        let details = fields(&[("Topic", &topic.name), ("Internal", &topic.internal), ("Messages", &topic.messages)]);
+        let partitions = table(&["PARTITION", "LEADER", "REPLICAS", "ISR", "LOW", "HIGH", "MESSAGES"], topic.partitions.iter()
+            .map(|p| vec![p.id.to_string(), p.leader.to_string(), join(&p.replicas), join(&p.isr),
+                or_dash(p.low_watermark), or_dash(p.high_watermark), or_dash(p.messages)])
+            .collect());
+        format!("{}\n\n{}", details, partitions)
+    })
+}
+
+fn groups_output(metadata: &Metadata, output: OutputFormat) -> Result<String, CliError> {
+    let groups = groups(metadata);
+    format(output, &groups, || table(&["GROUP", "STATE", "PROTOCOL TYPE", "PROTOCOL", "MEMBERS"], groups.iter()
+        .map(|g| vec![g.name.clone(), g.state.clone(), g.protocol_type.clone(), g.protocol.clone(), g.members.to_string()])
+        .collect()))
+}
+
+fn group_output(consumer: &Consumer<DefaultContext>, metadata: &Metadata, group: &str, output: OutputFormat,
+    client_config: &ClientConfig, context: DefaultContext) -> Result<String, CliError> {
+    let group = describe_group(consumer, metadata, group, client_config, context)?;
+    format(output, &group, || {
+        let details = fields(&[("Group", &group.name), ("State", &group.state), ("Protocol Type", &group.protocol_type),
+            ("Protocol", &group.protocol), ("Lag", &group.lag)]);
+        let members = table(&["MEMBER", "CLIENT", "HOST"], group.members.iter()
+            .map(|m| vec![m.id.clone(), m.client_id.clone(), m.client_host.clone()])
+            .collect());
+        let offsets = table(&["TOPIC", "PARTITION", "COMMITTED", "HIGH", "LAG"], group.offsets.iter()
+            .map(|o| vec![o.topic.clone(), o.partition.to_string(), o.committed.to_string(), o.high_watermark.to_string(), o.lag.to_string()])
+            .collect());
+        format!("{}\n\n{}\n\n{}", details, members, offsets)
+    })
+}
+
+// Print the messages of the partition from the start offset until the end offset or the count
+// without an end offset the partition is followed until the process is interrupted
+fn consume(topic: &str, partition: i32, range: ConsumeRange, output: OutputFormat, format: &str, client_config: &ClientConfig, context: DefaultContext) -> Result<(), CliError> {
+    let invalid_timestamp = |err: CommandError| CliError { message: err.to_string(), code: EXIT_INVALID_ARGS };
+    let from_timestamp = range.from_timestamp.as_deref().map(parse_timestamp).transpose().map_err(invalid_timestamp)?;
+    let to_timestamp = range.to_timestamp.as_deref().map(parse_timestamp).transpose().map_err(invalid_timestamp)?;
+
+    let mut consumer = Consumer::new(client_config, context, true)?;
+    check_exists(&mut consumer, topic, Some(partition))?;
+
+    // offsets for the timestamps are looked up among the assigned partitions
+    consumer.assign(topic, partition)?;
+    let watermarks = consumer.fetch_watermarks(topic, partition)?;
+    let start = match (range.from_offset, from_timestamp) {
+        (Some(offset), _) => consumer.resolve_offset(topic, partition, OffsetBound::Offset(offset), watermarks)?,
+        (_, Some(timestamp)) => consumer.resolve_offset(topic, partition, OffsetBound::Timestamp(timestamp), watermarks)?,
+        _ => watermarks.0,
+    };
+    let end = match (range.to_offset, to_timestamp) {
+        (Some(offset), _) => Some(consumer.resolve_offset(topic, partition, OffsetBound::Offset(offset), watermarks)?),
+        (_, Some(timestamp)) => Some(consumer.resolve_offset(topic, partition, OffsetBound::Timestamp(timestamp), watermarks)?),
+        _ if range.follow => None,
+        _ => Some(watermarks.1),
+    };
+
+    if range.count == Some(0) {
+        return Ok(());
+    }
+
+    let mut stdout = BufWriter::new(std::io::stdout().lock());
+    let (mut printed, mut written) = (0, Ok(()));
+    consumer.read_range(topic, partition, start, end, CONSUME_POLL_TIMEOUT, |message| {
+        let Some(message) = message else {
+            return true;
+        };
+
+        if let Err(err) = write_message(&mut stdout, message, output, format) {
+            written = Err(err);
+            return false;
+        }
+
+        printed += 1;
+        if range.count.is_some_and(|c| printed >= c) {
+            return false;
+        }
+
+        // followed messages are printed as soon as they arrive
+        if range.follow && let Err(err) = stdout.flush() {
+            written = Err(err);
+            return false;
+        }
+        true
+    })?;
+
+    let result = written.and_then(|_| stdout.flush());
+    match result {
+        // the reader of the output exited, e.g. head
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+        Err(err) => Err(CliError { message: format!("unable to write the messages: {}", err), code: EXIT_FAILURE }),
+        Ok(_) => Ok(()),
+    }
+}
+
+// Produce every line of the input as a record, failures are reported per line & the remaining lines are still produced
+fn produce(topic: &str, options: ProduceOptions, output: OutputFormat, client_config: &ClientConfig, context: DefaultContext) -> Result<(), CliError> {
+    let reader: Box<dyn BufRead> = match &options.input {
+        Some(path) => Box::new(BufReader::new(File::open(path)
+            .map_err(|err| CliError { message: format!("unable to open {}: {}", path.display(), err), code: EXIT_INVALID_ARGS })?)),
+        None => Box::new(std::io::stdin().lock()),
+    };
+
+    // records are not enqueued to a topic which would be auto created
+    let mut consumer = Consumer::new(client_config, context.clone(), true)?;
+    check_exists(&mut consumer, topic, options.partition)?;
+
+    let mut config = client_config.clone();
+    config.set(ACKS, options.acks.to_string());
+    config.set(COMPRESSION_TYPE, options.compression.to_string());
+    config.set(LINGER_MS, options.linger_ms.to_string());
+    config.set(BATCH_NUM_MESSAGES, options.batch_size.to_string());
+    let producer = Producer::new(&config, context)?;
+
+    let started = Instant::now();
+    let mut pending: VecDeque<(usize, DeliveryFuture)> = VecDeque::new();
+    let (mut sent, mut produced, mut failed) = (0, 0, 0);
+    let report = |line: usize, err: String, failed: &mut usize| {
+        eprintln!("line {}: {}", line, err);
+        *failed += 1;
+    };
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = match line {
+            Ok(line) => line,
+            // the line is skipped, e.g. when it is not valid UTF-8
+            Err(err) if err.kind() == ErrorKind::InvalidData => {
+                report(line_no, err.to_string(), &mut failed);
+                continue;
+            },
+            Err(err) => return Err(CliError { message: format!("unable to read the input: {}", err), code: EXIT_FAILURE }),
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record = match to_record(&line, options.jsonl, options.partition) {
+            Ok(record) => record,
+            Err(err) => {
+                report(line_no, err, &mut failed);
+                continue;
+            }
+        };
+
+        // records are spread evenly across each second
+        if let Some(rate) = options.rate {
+            let due = started + Duration::from_secs_f64(sent as f64 / rate as f64);
+            std::thread::sleep(due.saturating_duration_since(Instant::now()));
+        }
+
+        sent += 1;
+        match producer.send(topic, &record) {
+            Ok(delivery) => pending.push_back((line_no, delivery)),
+            Err(err) => report(line_no, err.to_string(), &mut failed),
+        }
+
+        while pending.len() > MAX_PENDING_DELIVERIES && let Some((line_no, delivery)) = pending.pop_front() {
+            match await_delivery(delivery) {
+                Ok(_) => produced += 1,
+                Err(err) => report(line_no, err.to_string(), &mut failed),
+            }
+        }
+    }
+
+    producer.flush()?;
+    for (line_no, delivery) in pending {
+        match await_delivery(delivery) {
+            Ok(_) => produced += 1,
+            Err(err) => report(line_no, err.to_string(), &mut failed),
+        }
+    }
+
+    let summary = ProduceSummary { topic: topic.to_string(), produced, failed };
+    println!("{}", format(output, &summary, || fields(&[("Topic", &summary.topic), ("Produced", &summary.produced), ("Failed", &summary.failed)]))?);
+    match failed {
+        0 => Ok(()),
+        _ => Err(CliError { message: format!("{} of {} records failed", failed, produced + failed), code: EXIT_FAILURE }),
+    }
+}
+
+// Record of a line, either the line as the payload or a JSON object
+fn to_record(line: &str, jsonl: bool, partition: Option<i32>) -> Result<ProducerRecord, String> {
+    if !jsonl {
+        return Ok(ProducerRecord { payload: Some(line.to_string()), partition, ..Default::default() });
+    }
+
+    let record = serde_json::from_str::<JsonRecord>(line).map_err(|err| format!("invalid JSON record: {}", err))?;
+    Ok(ProducerRecord {
+        key: record.key,
+        headers: record.headers,
+        payload: record.value.map(|v| match v {
+            Value::String(s) => s,
+            v => v.to_string(),
+        }),
+        partition: record.partition.or(partition),
+    })
+}
+
+// Topic & the partition exist, also fetches the metadata of the consumer
+fn check_exists(consumer: &mut Consumer<DefaultContext>, topic: &str, partition: Option<i32>) -> Result<(), CliError> {
+    let metadata = consumer.fetch_metadata()?;
+    consumer.update_metadata(metadata, vec![]);
+    let exists = consumer.metadata().get_topic(topic)
+        .ok_or(CliError::not_found(format!("topic {} not found", topic)))?
+        .partitions().iter()
+        .any(|p| partition.is_none_or(|id| p.id() == id));
+
+    match (exists, partition) {
+        (false, Some(partition)) => Err(CliError::not_found(format!("partition {}/{} not found", topic, partition))),
+        _ => Ok(()),
+    }
+}
+
+// Message as JSONL, a YAML document or in the given format
+fn write_message(writer: &mut impl Write, message: &KafkaMessage, output: OutputFormat, format: &str) -> std::io::Result<()> {
+    match output {
+        OutputFormat::Table => writer.write_all(&format_message(format, message)),
+        OutputFormat::Json => writeln!(writer, "{}", message.to_raw_json()),
+        OutputFormat::Yaml => {
+            let yaml = serde_yaml_ng::to_string(&message.to_raw_json()).map_err(std::io::Error::other)?;
+            writeln!(writer, "---\n{}", yaml.trim_end())
+        },
+    }
+}
+
+// Message in a format like the one of kcat, e.g. '%k %s\n'
+// %t topic, %p partition, %o offset, %k key, %s payload, %K & %S their lengths, %T timestamp & %h headers in the order they were produced
+// missing keys & payloads are empty and their lengths & missing timestamps are -1
+fn format_message(format: &str, message: &KafkaMessage) -> Vec<u8> {
+    let mut formatted = vec![];
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => match chars.next() {
+                Some('t') => formatted.extend_from_slice(message.topic.as_bytes()),
+                Some('p') => formatted.extend_from_slice(message.partition.to_string().as_bytes()),
+                Some('o') => formatted.extend_from_slice(message.offset.to_string().as_bytes()),
+                Some('k') => formatted.extend_from_slice(message.raw_key().unwrap_or_default()),
+                Some('s') => formatted.extend_from_slice(message.raw_payload().unwrap_or_default()),
+                Some('K') => formatted.extend_from_slice(message.raw_key().map_or(-1, |k| k.len() as i64).to_string().as_bytes()),
+                Some('S') => formatted.extend_from_slice(message.raw_payload().map_or(-1, |p| p.len() as i64).to_string().as_bytes()),
+                Some('T') => formatted.extend_from_slice(message.timestamp.unwrap_or(-1).to_string().as_bytes()),
+                Some('h') => {
+                    for (idx, (key, value)) in message.raw_headers.iter().enumerate() {
+                        if idx > 0 {
+                            formatted.push(b',');
+                        }
+                        formatted.extend_from_slice(key.as_bytes());
+                        formatted.push(b'=');
+                        formatted.extend_from_slice(value.as_deref().unwrap_or_default());
+                    }
+                },
+                Some('%') => formatted.push(b'%'),
+                // unknown tokens are printed as they are
+                Some(other) => {
+                    formatted.push(b'%');
+                    push_char(&mut formatted, other);
+                },
+                None => formatted.push(b'%'),
+            },
+            '\\' => match chars.next() {
+                Some('n') => formatted.push(b'\n'),
+                Some('t') => formatted.push(b'\t'),
+                Some('r') => formatted.push(b'\r'),
+                Some('\\') => formatted.push(b'\\'),
+                Some(other) => {
+                    formatted.push(b'\\');
+                    push_char(&mut formatted, other);
+                },
+                None => formatted.push(b'\\'),
+            },
+            _ => push_char(&mut formatted, c),
+        }
+    }
+
+    formatted
+}
+
+fn push_char(bytes: &mut Vec<u8>, c: char) {
+    let mut buf = [0; 4];
+    bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+}
+
+fn brokers(metadata: &Metadata) -> Vec<BrokerSummary> {
+    let mut brokers = metadata.brokers().iter()
+        .map(|b| BrokerSummary {
+            id: b.id(),
+            name: b.name().to_string(),
+            leader_of: metadata.no_of_partitions_for_broker(b.id()),
+            replica_of: metadata.no_of_replicas_for_broker(b.id()),
+        })
+        .collect::<Vec<BrokerSummary>>();
+
+    brokers.sort_by_key(|b| b.id);
+    brokers
+}
+
+fn topics(metadata: &Metadata) -> Vec<TopicSummary> {
+    let mut topics = metadata.topics().iter()
+        .map(|t| TopicSummary {
+            name: t.name().to_string(),
+            internal: is_internal_topic(t.name()),
+            partitions: t.partitions().len(),
+            replication_factor: t.partitions().iter().map(|p| p.replicas().len()).max().unwrap_or_default(),
+            under_replicated: t.partitions().iter().filter(|p| p.is_under_replicated()).count(),
+            offline: t.partitions().iter().filter(|p| p.is_offline()).count(),
+        })
+        .collect::<Vec<TopicSummary>>();
+
+    topics.sort_by(|a, b| a.name.cmp(&b.name));
+    topics
+}
+
+fn describe_topic(consumer: &Consumer<DefaultContext>, metadata: &Metadata, name: &str) -> Result<TopicDetails, CliError> {
+    let topic = metadata.get_topic(name)
+        .ok_or(CliError::not_found(format!("topic {} not found", name)))?;
+
+    let mut partitions = vec![];
+    for partition in topic.partitions() {
+        // offline partitions would only time out
+        let watermarks = match partition.is_offline() {
+            true => None,
+            false => Some(consumer.fetch_watermarks(topic.name(), partition.id())?),
+        };
+
+        partitions.push(PartitionDetails {
+            id: partition.id(),
+            leader: partition.leader(),
+            replicas: partition.replicas(),
+            isr: partition.isr(),
+            low_watermark: watermarks.map(|(low, _)| low),
+            high_watermark: watermarks.map(|(_, high)| high),
+            messages: watermarks.map(|(low, high)| high - low),
+        });
+    }
+
+    partitions.sort_by_key(|p| p.id);
+    Ok(TopicDetails {
+        name: topic.name().to_string(),
+        internal: is_internal_topic(topic.name()),
+        messages: partitions.iter().filter_map(|p| p.messages).sum(),
+        partitions,
+    })
+}
+
+fn groups(metadata: &Metadata) -> Vec<GroupSummary> {
+    let mut groups = metadata.consumer_groups().iter()
+        .map(|g| GroupSummary {
+            name: g.name().to_string(),
+            state: g.state().to_string(),
+            protocol_type: g.protocol_type().to_string(),
+            protocol: g.protocol().to_string(),
+            members: g.members_count(),
+        })
+        .collect::<Vec<GroupSummary>>();
+
+    groups.sort_by(|a, b| a.name.cmp(&b.name));
+    groups
+}
+
+// Committed offsets can only be fetched by a consumer under the group, the consumer never joins the group or commits offsets
+fn describe_group(consumer: &Consumer<DefaultContext>, metadata: &Metadata, name: &str, client_config: &ClientConfig, context: DefaultContext) -> Result<GroupDetails, CliError> {
+    let group = metadata.get_consumer_group(name)
+        .ok_or(CliError::not_found(format!("consumer group {} not found", name)))?;
+
+    let mut partitions = TopicPartitionList::new();
+    for topic in metadata.topics().iter().filter(|t| !is_internal_topic(t.name())) {
+        for partition in topic.partitions().iter().filter(|p| !p.is_offline()) {
+            partitions.add_partition(topic.name(), partition.id());
+        }
+    }
+
+    let mut config = client_config.clone();
+    config.set(GROUP_ID, name);
+    let group_consumer = Consumer::new(&config, context, true)?;
+    let committed = group_consumer.committed_offsets(&partitions)?;
+
+    // watermarks are fetched only for the partitions with committed offsets
+    let mut offsets = vec![];
+    for element in committed.elements() {
+        if let Offset::Offset(offset) = element.offset() {
+            let (_, high) = consumer.fetch_watermarks(element.topic(), element.partition())?;
+            offsets.push(OffsetDetails {
+                topic: element.topic().to_string(),
+                partition: element.partition(),
+                committed: offset,
+                high_watermark: high,
+                lag: (high - offset).max(0),
+            });
+        }
+    }
+
+    offsets.sort_by(|a, b| a.topic.cmp(&b.topic).then(a.partition.cmp(&b.partition)));
+    Ok(GroupDetails {
+        name: group.name().to_string(),
+        state: group.state().to_string(),
+        protocol_type: group.protocol_type().to_string(),
+        protocol: group.protocol().to_string(),
+        members: group.members().iter()
+            .map(|m| MemberDetails {
+                id: m.id().to_string(),
+                client_id: m.client_id().to_string(),
+                client_host: m.client_host().to_string(),
+            })
+            .collect(),
+        lag: offsets.iter().map(|o| o.lag).sum(),
+        offsets,
+    })
+}
+
+// Format the value as JSON or YAML, or as the table built by the caller
+fn format<S: Serialize>(output: OutputFormat, value: &S, table: impl FnOnce() -> String) -> Result<String, CliError> {
+    let serialize_error = |err: String| CliError { message: format!("unable to serialize the output: {}", err), code: EXIT_FAILURE };
+
+    match output {
+        OutputFormat::Table => Ok(table()),
+        // fields are kept in the order of the struct
+        OutputFormat::Json => serde_json::to_string_pretty(value).map_err(|err| serialize_error(err.to_string())),
+        OutputFormat::Yaml => serde_yaml_ng::to_string(value)
+            .map(|yaml| yaml.trim_end().to_string())
+            .map_err(|err| serialize_error(err.to_string())),
+    }
+}
+
+// Columns aligned to the widest value of each column
+fn table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths = headers.iter().map(|h| h.len()).collect::<Vec<usize>>();
+    for row in &rows {
+        for (idx, value) in row.iter().enumerate() {
+            widths[idx] = widths[idx].max(value.chars().count());
+        }
+    }
+
+    let line = |values: Vec<String>| values.iter().enumerate()
+        .map(|(idx, v)| format!("{:<width$}", v, width = widths[idx]))
+        .collect::<Vec<String>>()
+        .join("  ")
+        .trim_end()
+        .to_string();
+
+    let mut lines = vec![line(headers.iter().map(|h| h.to_string()).collect())];
+    lines.extend(rows.into_iter().map(line));
+    lines.join("\n")
+}
+
+// Fields aligned by their names, e.g. "Topic    : orders"
+fn fields(fields: &[(&str, &dyn Display)]) -> String {
+    let width = fields.iter().map(|(name, _)| name.len()).max().unwrap_or_default();
+    fields.iter()
+        .map(|(name, value)| format!("{:<width$} : {}", name, value, width = width))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn join(ids: &[i32]) -> String {
+    ids.iter().map(|id| id.to_string()).collect::<Vec<String>>().join(",")
+}
+
+fn or_dash(value: Option<i64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or("-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> KafkaMessage {
+        KafkaMessage {
+            topic: "orders".to_string(),
+            partition: 3,
+            offset: 1200,
+            key: Some("order-1".to_string()),
+            binary_key: None,
+            headers: HashMap::from([("source".to_string(), "web".to_string())]),
+            raw_headers: vec![("source".to_string(), Some(b"web".to_vec()))],
+            payload: Some("{\"total\": 12}".to_string()),
+            binary_payload: None,
+            timestamp: Some(1714557600000),
+        }
+    }
+
+    fn formatted(format: &str, message: &KafkaMessage) -> String {
+        String::from_utf8(format_message(format, message)).unwrap()
+    }
+
+    #[test]
+    fn format_message_tokens() {
+        assert_eq!(formatted("%t/%p@%o %T", &message()), "orders/3@1200 1714557600000");
+        assert_eq!(formatted("%k (%K) %s (%S)", &message()), "order-1 (7) {\"total\": 12} (13)");
+        assert_eq!(formatted("%h", &message()), "source=web");
+    }
+
+    #[test]
+    fn format_message_without_key_payload_or_timestamp() {
+        let message = KafkaMessage { key: None, payload: None, timestamp: None, headers: HashMap::new(), raw_headers: vec![], ..message() };
+        assert_eq!(formatted("[%k] %K [%s] %S %T [%h]", &message), "[] -1 [] -1 -1 []");
+    }
+
+    #[test]
+    fn format_message_with_binary_key_and_payload() {
+        let message = KafkaMessage {
+            key: None,
+            binary_key: Some(vec![0xff, 0x00]),
+            payload: None,
+            binary_payload: Some(vec![0xde, 0xad, 0xbe, 0xef]),
+            ..message()
+        };
+
+        assert_eq!(format_message("%k|%K|%s|%S", &message), [&[0xff, 0x00][..], b"|2|", &[0xde, 0xad, 0xbe, 0xef], b"|4"].concat());
+    }
+
+    #[test]
+    fn format_message_escapes() {
+        assert_eq!(formatted("%o\\n%o\\t\\r\\\\", &message()), "1200\n1200\t\r\\");
+        assert_eq!(formatted("100%% %o", &message()), "100% 1200");
+    }
+
+    #[test]
+    fn format_message_of_malformed_input() {
+        // unknown tokens & escapes are printed as they are, as are a trailing % or backslash
+        assert_eq!(formatted("%x \\q ü %", &message()), "%x \\q ü %");
+        assert_eq!(formatted("\\", &message()), "\\");
+        assert_eq!(formatted("", &message()), "");
+    }
+
+    fn topic_details() -> TopicDetails {
+        TopicDetails {
+            name: "orders".to_string(),
+            internal: false,
+            messages: 42,
+            partitions: vec![PartitionDetails {
+                id: 0,
+                leader: 1,
+                replicas: vec![1, 2],
+                isr: vec![],
+                low_watermark: Some(0),
+                high_watermark: None,
+                messages: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn format_yaml_in_the_order_of_the_struct() {
+        let yaml = format(OutputFormat::Yaml, &topic_details(), String::new).unwrap();
+        let expected = "name: orders\ninternal: false\nmessages: 42\npartitions:\n- id: 0\n  leader: 1\n  replicas:\n  - 1\n  - 2\n  isr: []\n  low_watermark: 0\n  high_watermark: null\n  messages: null";
+        assert_eq!(yaml, expected);
+    }
+
+    #[test]
+    fn format_yaml_round_trip() {
+        let details = GroupDetails {
+            name: "billing: eu".to_string(),
+            state: "true".to_string(),
+            protocol_type: "".to_string(),
+            protocol: "null".to_string(),
+            members: vec![MemberDetails { id: "- 1".to_string(), client_id: "1.0".to_string(), client_host: "/10.0.0.1\n#".to_string() }],
+            lag: -1,
+            offsets: vec![],
+        };
+
+        let yaml = format(OutputFormat::Yaml, &details, String::new).unwrap();
+        let json = format(OutputFormat::Json, &details, String::new).unwrap();
+        assert_eq!(serde_yaml_ng::from_str::<Value>(&yaml).unwrap(), serde_json::from_str::<Value>(&json).unwrap());
+    }
+
+    #[test]
+    fn yaml_keys_in_the_order_of_json() {
+        let json = format(OutputFormat::Json, &topic_details(), String::new).unwrap();
+        let yaml = format(OutputFormat::Yaml, &topic_details(), String::new).unwrap();
+
+        let keys = |value: Value| value.as_object().unwrap().keys().cloned().collect::<Vec<String>>();
+        assert_eq!(keys(serde_yaml_ng::from_str(&yaml).unwrap()), keys(serde_json::from_str(&json).unwrap()));
+    }
+
+    #[test]
+    fn write_messages_as_yaml_documents() {
+        let mut written = vec![];
+        write_message(&mut written, &message(), OutputFormat::Yaml, "").unwrap();
+        write_message(&mut written, &message(), OutputFormat::Yaml, "").unwrap();
+
+        let written = String::from_utf8(written).unwrap();
+        assert!(written.starts_with("---\ntopic: orders\npartition: 3\noffset: 1200\n"));
+
+        let documents = written.split("---\n").filter(|d| !d.is_empty()).collect::<Vec<&str>>();
+        assert_eq!(documents.len(), 2);
+        assert_eq!(serde_yaml_ng::from_str::<Value>(documents[0]).unwrap(), message().to_raw_json());
+    }
+
+    #[test]
+    fn format_message_headers_in_the_order_they_were_produced() {
+        let message = KafkaMessage {
+            raw_headers: vec![("b".to_string(), Some(b"2".to_vec())), ("a".to_string(), None), ("b".to_string(), Some(b"3".to_vec()))],
+            ..message()
+        };
+        assert_eq!(formatted("%h", &message), "b=2,a=,b=3");
+    }
+
+    #[test]
+    fn write_messages_as_jsonl_as_they_were_produced() {
+        let message = KafkaMessage {
+            key: None,
+            binary_key: Some(vec![0xff]),
+            raw_headers: vec![("trace".to_string(), None), ("blob".to_string(), Some(vec![0xfe, 0x01]))],
+            payload: Some("{\"total\":12, \"currency\":\"EUR\"}".to_string()),
+            ..message()
+        };
+
+        let mut written = vec![];
+        write_message(&mut written, &message, OutputFormat::Json, "").unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), concat!(
+            r#"{"topic":"orders","partition":3,"offset":1200,"timestamp":1714557600000,"key":null,"key_base64":"/w==","#,
+            r#""headers":[{"key":"trace","value":null},{"key":"blob","value":null,"value_base64":"/gE="}],"#,
+            r#""payload":"{\"total\":12, \"currency\":\"EUR\"}"}"#,
+            "\n"
+        ));
+    }
+}
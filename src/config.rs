@@ -1,21 +1,30 @@
-use std::{error::Error, fmt::Display};
+use std::{error::Error, fmt::Display, path::{Path, PathBuf}};
 
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use log::info;
 use rdkafka::config::{ClientConfig, RDKafkaLogLevel};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use strum::{Display};
 
 // connection config params
 const BOOTSTRAP_SERVERS: &str = "bootstrap.servers";
-const GROUP_ID: &str = "group.id";
+pub const GROUP_ID: &str = "group.id";
 const SOCKET_KEEP_ALIVE: &str = "socket.keepalive.enable";
 const STATS_INTERVAL_MS: &str = "statistics.interval.ms";
+pub const ENABLE_AUTO_COMMIT: &str = "enable.auto.commit";
+pub const ENABLE_AUTO_OFFSET_STORE: &str = "enable.auto.offset.store";
 const SECURITY_PROTOCOL: &str = "security.protocol";
 
 // SSL config
 const SSL_KEY_LOCATION: &str = "ssl.key.location";
-const SSL_CERT_LOCATION: &str = "ss.certificate.location";
+const SSL_CERT_LOCATION: &str = "ssl.certificate.location";
 const CA_CERT_LOCATION: &str = "ssl.ca.location";
+const SSL_KEY_PEM: &str = "ssl.key.pem";
+const SSL_KEY_PASSWORD: &str = "ssl.key.password";
+const SSL_CERT_PEM: &str = "ssl.certificate.pem";
+const CA_CERT_PEM: &str = "ssl.ca.pem";
+const SSL_KEYSTORE_LOCATION: &str = "ssl.keystore.location";
+const SSL_KEYSTORE_PASSWORD: &str = "ssl.keystore.password";
 const ENABLE_CERT_VALIDATION: &str = "enable.ssl.certificate.verification";
 
 // SASL mechanism
@@ -33,10 +42,21 @@ const OAUTH_SCOPE: &str = "sasl.oauthbearer.scope";
 const OAUTH_TOKEN_ENDPOINT: &str = "sasl.oauthbearer.token.endpoint.url";
 const HTTPS_CA_LOCATION: &str = "https.ca.location";
 
+// Producer config
+pub const ACKS: &str = "acks";
+pub const COMPRESSION_TYPE: &str = "compression.type";
+pub const LINGER_MS: &str = "linger.ms";
+pub const BATCH_NUM_MESSAGES: &str = "batch.num.messages";
+
 // Log config
 const DEBUG: &str = "debug";
 
+const APP_DIR: &str = "kafka2i";
 const DEFAULT_GROUP_ID: &str = "cg.krust";
+const DEFAULT_ACTIVITY_REFRESH_IN_SECS: &str = "60";
+const DEFAULT_CERT_EXPIRY_WARNING_DAYS: &str = "30";
+const DEFAULT_LOG_MAX_SIZE_MB: &str = "10";
+const DEFAULT_LOG_KEEP_FILES: &str = "5";
 
 #[derive(Debug, Display, Clone, ValueEnum, PartialEq, Copy)]
 pub enum LogLevel {
@@ -78,6 +98,177 @@ pub enum SaslMechanism {
     OauthBearer,
 }
 
+#[derive(Debug, Display, Clone, Copy, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapPreset {
+    #[default]
+    #[strum(serialize = "default")]
+    Default,
+    #[strum(serialize = "vim")]
+    Vim,
+}
+
+#[derive(Debug, Display, Clone, Copy, Default, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemePreset {
+    #[default]
+    #[strum(serialize = "dark")]
+    Dark,
+    #[strum(serialize = "light")]
+    Light,
+    #[strum(serialize = "high-contrast")]
+    HighContrast,
+    #[strum(serialize = "no-color")]
+    NoColor,
+}
+
+// Format of the output of the subcommands
+#[derive(Debug, Display, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    #[strum(serialize = "table")]
+    Table,
+    #[strum(serialize = "json")]
+    Json,
+    #[strum(serialize = "yaml")]
+    Yaml,
+}
+
+// Acknowledgements the producer waits for
+#[derive(Debug, Display, Clone, Copy, ValueEnum)]
+pub enum Acks {
+    #[strum(serialize = "all")]
+    #[value(name = "all")]
+    All,
+    #[strum(serialize = "1")]
+    #[value(name = "1")]
+    Leader,
+    #[strum(serialize = "0")]
+    #[value(name = "0")]
+    None,
+}
+
+#[derive(Debug, Display, Clone, Copy, ValueEnum)]
+pub enum Compression {
+    #[strum(serialize = "none")]
+    None,
+    #[strum(serialize = "gzip")]
+    Gzip,
+    #[strum(serialize = "snappy")]
+    Snappy,
+    #[strum(serialize = "lz4")]
+    Lz4,
+    #[strum(serialize = "zstd")]
+    Zstd,
+}
+
+// Subcommands printing the metadata or the messages of the cluster instead of starting the TUI, e.g. in scripts
+#[derive(Debug, Clone, Subcommand)]
+pub enum CliCommand {
+    /// List or describe the topics
+    Topics {
+        #[command(subcommand)]
+        command: TopicsCommand,
+    },
+    /// List or describe the consumer groups
+    Groups {
+        #[command(subcommand)]
+        command: GroupsCommand,
+    },
+    /// List the brokers
+    Brokers,
+    /// Print the messages of a partition, as JSONL with `--output json`
+    Consume {
+        /// Name of the topic
+        topic: String,
+        /// Partition to consume from
+        #[arg(short, long, default_value_t = 0)]
+        partition: i32,
+        /// Offset to start from, defaults to the low watermark
+        #[arg(long, conflicts_with = "from_timestamp")]
+        from_offset: Option<i64>,
+        /// Timestamp to start from, either epoch in millis or ISO 8601
+        #[arg(long)]
+        from_timestamp: Option<String>,
+        /// Offset to stop at, the message at the offset is not printed. Defaults to the high watermark
+        #[arg(long, conflicts_with = "to_timestamp")]
+        to_offset: Option<i64>,
+        /// Timestamp to stop at, either epoch in millis or ISO 8601
+        #[arg(long)]
+        to_timestamp: Option<String>,
+        /// Format of the messages in the table output, e.g. '%k %s\n'
+        #[arg(short, long, default_value = "%s\\n")]
+        format: String,
+        /// Stop after printing these many messages
+        #[arg(short, long)]
+        count: Option<usize>,
+        /// Wait for new messages instead of stopping at the end of the partition
+        #[arg(long, conflicts_with_all = ["to_offset", "to_timestamp"])]
+        follow: bool,
+    },
+    /// Produce every line of a file or stdin as a record, as JSONL with `--jsonl`
+    Produce {
+        /// Name of the topic
+        topic: String,
+        /// File to read the records from, defaults to stdin
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// Read every line as a JSON object with key, headers, value & partition fields
+        #[arg(long)]
+        jsonl: bool,
+        /// Partition to produce to, defaults to the partitioner. The partition of a JSONL record takes precedence
+        #[arg(short, long)]
+        partition: Option<i32>,
+        /// Acknowledgements to wait for, should be one of `all`, `1`, `0`
+        #[arg(long, value_enum, default_value_t = Acks::All)]
+        acks: Acks,
+        /// Compression of the batches
+        #[arg(long, value_enum, default_value_t = Compression::None)]
+        compression: Compression,
+        /// Time in milliseconds to wait for more records to fill a batch
+        #[arg(long, default_value_t = 5)]
+        linger_ms: u64,
+        /// Maximum number of records in a batch
+        #[arg(long, default_value_t = 10000)]
+        batch_size: u64,
+        /// Maximum number of records produced per second
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        rate: Option<u32>,
+    },
+}
+
+impl CliCommand {
+    // Subcommands which write to the cluster, these are refused in read-only mode
+    pub fn is_write(&self) -> bool {
+        match self {
+            CliCommand::Produce { .. } => true,
+            CliCommand::Topics { .. } | CliCommand::Groups { .. } | CliCommand::Brokers | CliCommand::Consume { .. } => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum TopicsCommand {
+    /// List the topics along with their partitions & replication
+    List,
+    /// Describe the partitions of the topic along with their watermarks
+    Describe {
+        /// Name of the topic
+        topic: String,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum GroupsCommand {
+    /// List the consumer groups along with their state & members
+    List,
+    /// Describe the members of the consumer group along with its committed offsets & lag
+    Describe {
+        /// Name of the consumer group
+        group: String,
+    },
+}
+
 impl Into<RDKafkaLogLevel> for LogLevel {
     fn into(self) -> RDKafkaLogLevel {
         match self {
@@ -94,7 +285,7 @@ pub struct ConfigError {
 }
 
 impl ConfigError {
-    fn new(message: &str) -> ConfigError {
+    pub fn new(message: &str) -> ConfigError {
         ConfigError {
             message: message.to_string(),
         }
@@ -122,18 +313,74 @@ impl Error for ConfigError {
 #[command(name = "kafka2i")]
 #[command(about = "TUI for kafka written in Rust", long_about = None)]
 pub struct Config {
+    /// Print the metadata or the messages of the cluster instead of starting the TUI
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+
+    /// Format of the output of the subcommands
+    #[arg(long, value_enum, global = true, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+
     /// Log level to be set for kafka client
     #[arg(short, long, value_enum, default_value_t = LogLevel::Info)]
     pub log_level: LogLevel,
 
-    /// Bootstrap servers in kafka format
-    #[arg(short, long, required=true)]
+    /// Directory for the log files, defaults to logs in the state directory
+    #[arg(long, env = "KAFKA2I_LOG_DIR")]
+    pub log_dir: Option<String>,
+
+    /// Size in MB after which the log file is rotated, log files are also rotated daily
+    #[arg(long, default_value = DEFAULT_LOG_MAX_SIZE_MB)]
+    pub log_max_size_mb: u64,
+
+    /// Number of rotated log files to keep
+    #[arg(long, default_value = DEFAULT_LOG_KEEP_FILES)]
+    pub log_keep_files: usize,
+
+    /// Number of days to keep rotated log files for, replaces the number of files to keep
+    #[arg(long, conflicts_with = "log_keep_files")]
+    pub log_keep_days: Option<usize>,
+
+    /// Disable writing logs to files, logs are still shown in the log pane
+    #[arg(long, conflicts_with_all = ["log_dir", "log_max_size_mb", "log_keep_files", "log_keep_days"])]
+    pub no_log_file: bool,
+
+    /// Bootstrap servers in kafka format, defaults to the cluster of the previous session
+    #[arg(short, long, default_value_t = String::new(), hide_default_value = true)]
     pub bootstrap_servers: String,
     
     /// Consumer group ID
     #[arg(short, long, default_value = DEFAULT_GROUP_ID)]
     pub group_id: String,
 
+    /// Read-only mode, refuses offset commits & any write operation on the cluster
+    #[arg(long)]
+    pub read_only: bool,
+
+    /// Interval in seconds of fetching the size & activity of the topics and lag of the consumer groups
+    #[arg(long, default_value = DEFAULT_ACTIVITY_REFRESH_IN_SECS)]
+    pub activity_refresh_in_secs: u64,
+
+    /// Disable fetching the size & activity of the topics and lag of the consumer groups
+    #[arg(long)]
+    pub no_activity: bool,
+
+    /// Topic to open the app at, replaces the position of the previous session
+    #[arg(long)]
+    pub topic: Option<String>,
+
+    /// Partition of the topic to open the app at
+    #[arg(long, requires = "topic")]
+    pub partition: Option<i32>,
+
+    /// Offset of the partition to open the app at, defaults to the latest message
+    #[arg(long, requires = "partition", conflicts_with = "timestamp")]
+    pub offset: Option<i64>,
+
+    /// Timestamp to open the partition at, either epoch in milliseconds or ISO 8601
+    #[arg(long, requires = "partition")]
+    pub timestamp: Option<String>,
+
     /// Protocol to use
     #[arg(short, long, default_value_t = Protocol::Ssl)]
     pub protocol: Protocol,
@@ -142,18 +389,46 @@ pub struct Config {
     #[arg(long)]
     pub ssl_ca_location: Option<String>,
 
+    /// CA certificate/s in PEM format for validating SSL certificate
+    #[arg(long, env = "KAFKA2I_SSL_CA_PEM", hide_env_values = true, conflicts_with = "ssl_ca_location")]
+    pub ssl_ca_pem: Option<String>,
+
     /// SSL client key
     #[arg(long)]
     pub ssl_client_key_location: Option<String>,
 
+    /// SSL client key in PEM format
+    #[arg(long, env = "KAFKA2I_SSL_CLIENT_KEY_PEM", hide_env_values = true, conflicts_with = "ssl_client_key_location")]
+    pub ssl_client_key_pem: Option<String>,
+
+    /// Password of the encrypted SSL client key
+    #[arg(long, env = "KAFKA2I_SSL_CLIENT_KEY_PASSWORD", hide_env_values = true)]
+    pub ssl_client_key_password: Option<String>,
+
     /// SSL client certificate
     #[arg(long)]
     pub ssl_client_certificate_location: Option<String>,
 
+    /// SSL client certificate in PEM format
+    #[arg(long, env = "KAFKA2I_SSL_CLIENT_CERTIFICATE_PEM", hide_env_values = true, conflicts_with = "ssl_client_certificate_location")]
+    pub ssl_client_certificate_pem: Option<String>,
+
+    /// PKCS#12 keystore containing SSL client key & certificate
+    #[arg(long, conflicts_with_all = ["ssl_client_key_location", "ssl_client_key_pem", "ssl_client_certificate_location", "ssl_client_certificate_pem"])]
+    pub ssl_keystore_location: Option<String>,
+
+    /// PKCS#12 keystore password
+    #[arg(long, env = "KAFKA2I_SSL_KEYSTORE_PASSWORD", hide_env_values = true, requires = "ssl_keystore_location")]
+    pub ssl_keystore_password: Option<String>,
+
     /// Disable server certificate vertification
     #[arg(short, long)]
     pub disable_ssl_cert_vertification: bool,
 
+    /// Number of days before expiry to start warning about CA & client certificates
+    #[arg(long, default_value = DEFAULT_CERT_EXPIRY_WARNING_DAYS)]
+    pub cert_expiry_warning_days: i32,
+
     // SASL mechanism
     #[arg(long)]
     pub sasl_mechanism: Option<SaslMechanism>,
@@ -191,6 +466,89 @@ pub struct Config {
     /// Https CA location will be used to validate server cerification for the token endpoint
     #[arg(long)]
     pub https_ca_location: Option<String>,
+
+    /// Keymap file, defaults to keymap.toml in the config directory if present
+    #[arg(long)]
+    pub keymap: Option<String>,
+
+    /// Built in keymap to be used as base for the key bindings
+    #[arg(long, value_enum)]
+    pub keymap_preset: Option<KeymapPreset>,
+
+    /// Copy every fetched message to the clipboard
+    #[arg(long)]
+    pub auto_copy: bool,
+
+    /// Theme file, defaults to theme.toml in the config directory if present
+    #[arg(long)]
+    pub theme: Option<String>,
+
+    /// Built in theme to be used as base for the colors, no-color is used if NO_COLOR is set
+    #[arg(long, value_enum)]
+    pub theme_preset: Option<ThemePreset>,
+}
+
+// Directory to look for config files, e.g. ~/.config/kafka2i
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join(APP_DIR))
+}
+
+// Directory to persist state between sessions, e.g. ~/.local/state/kafka2i
+// falls back to the local data directory on platforms without a state directory
+pub fn state_dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|d| d.join(APP_DIR))
+}
+
+// Load the file persisted in the state directory, TOML or JSON picked by the extension of the file
+// falls back to the default as the state is not essential to run the app, e.g. a corrupt file is only logged
+pub fn load_state<T: DeserializeOwned + Default>(file: &str) -> T {
+    let Some(location) = state_dir().map(|d| d.join(file)).filter(|l| l.exists()) else {
+        return T::default();
+    };
+
+    read_file(&location).unwrap_or_else(|err| {
+        log::warn!("unable to load {}: {}", location.display(), err);
+        T::default()
+    })
+}
+
+// Persist the file in the state directory for the next session, failures are only logged
+pub fn save_state<T: Serialize>(file: &str, state: &T) {
+    let Some(location) = state_dir().map(|d| d.join(file)) else {
+        log::warn!("unable to save {}: no state directory found", file);
+        return;
+    };
+
+    if let Err(err) = write_file(&location, state) {
+        log::warn!("unable to save {}: {}", location.display(), err);
+    }
+}
+
+// Read the TOML or JSON file, picked by the extension of the file
+pub fn read_file<T: DeserializeOwned>(location: &Path) -> Result<T, String> {
+    let content = std::fs::read_to_string(location).map_err(|err| err.to_string())?;
+    match is_toml(location) {
+        true => toml::from_str(&content).map_err(|err| err.to_string()),
+        false => serde_json::from_str(&content).map_err(|err| err.to_string()),
+    }
+}
+
+pub fn write_file<T: Serialize>(location: &Path, state: &T) -> Result<(), String> {
+    let content = match is_toml(location) {
+        true => toml::to_string(state).map_err(|err| err.to_string())?,
+        false => serde_json::to_string_pretty(state).map_err(|err| err.to_string())?,
+    };
+
+    if let Some(dir) = location.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    }
+    std::fs::write(location, content).map_err(|err| err.to_string())
+}
+
+fn is_toml(location: &Path) -> bool {
+    location.extension().is_some_and(|e| e == "toml")
 }
 
 impl TryInto<ClientConfig> for Config {
@@ -209,12 +567,18 @@ impl TryInto<ClientConfig> for Config {
         if self.bootstrap_servers != "" {
             client_config.set(BOOTSTRAP_SERVERS.to_string(), self.bootstrap_servers);
         } else {
-            return Err(ConfigError::new("bootstrap servers cannot be empty"));
+            return Err(ConfigError::new("bootstrap servers cannot be empty, no cluster is saved from a previous session"));
         }
 
         // group id
         client_config.set(GROUP_ID.to_string(), self.group_id);
 
+        // never commit offsets in read-only mode
+        if self.read_only {
+            client_config.set(ENABLE_AUTO_COMMIT, "false");
+            client_config.set(ENABLE_AUTO_OFFSET_STORE, "false");
+        }
+
         // keepalive
         client_config.set(SOCKET_KEEP_ALIVE, "true");
 
@@ -227,16 +591,34 @@ impl TryInto<ClientConfig> for Config {
         // handle SSL config
         match self.protocol {
             Protocol::Ssl | Protocol::SaslSsl => {
-                if self.ssl_ca_location != None {
-                    client_config.set(CA_CERT_LOCATION, self.ssl_ca_location.unwrap());
+                // CA can either be a file or inline PEM
+                if let Some(ca_location) = self.ssl_ca_location {
+                    client_config.set(CA_CERT_LOCATION, ca_location);
+                } else if let Some(ca_pem) = self.ssl_ca_pem {
+                    client_config.set(CA_CERT_PEM, ca_pem);
                 } else {
-                    info!("ssl.ca.location is not provided, client will fall back to default ca location");
+                    info!("neither ssl.ca.location nor ssl.ca.pem is provided, client will fall back to default ca location");
                 }
 
-                // check if both client key & certificate is provided
-                if self.ssl_client_key_location != None && self.ssl_client_certificate_location != None {
-                    client_config.set(SSL_KEY_LOCATION, self.ssl_client_key_location.unwrap());
-                    client_config.set(SSL_CERT_LOCATION, self.ssl_client_certificate_location.unwrap());
+                // client key & certificate can either be files or inline PEMs
+                let client_key = self.ssl_client_key_location.map(|l| (SSL_KEY_LOCATION, l))
+                    .or(self.ssl_client_key_pem.map(|p| (SSL_KEY_PEM, p)));
+                let client_cert = self.ssl_client_certificate_location.map(|l| (SSL_CERT_LOCATION, l))
+                    .or(self.ssl_client_certificate_pem.map(|p| (SSL_CERT_PEM, p)));
+
+                // keystore replaces both client key & certificate
+                if let Some(keystore_location) = self.ssl_keystore_location {
+                    client_config.set(SSL_KEYSTORE_LOCATION, keystore_location);
+                    if let Some(keystore_password) = self.ssl_keystore_password {
+                        client_config.set(SSL_KEYSTORE_PASSWORD, keystore_password);
+                    }
+                } else if let (Some((key_param, key)), Some((cert_param, cert))) = (client_key, client_cert) {
+                    // check if both client key & certificate is provided
+                    client_config.set(key_param, key);
+                    client_config.set(cert_param, cert);
+                    if let Some(key_password) = self.ssl_client_key_password {
+                        client_config.set(SSL_KEY_PASSWORD, key_password);
+                    }
                 } else {
                     info!("either of client key, client cert or both are not provided, wil continue without using both")
                 }
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local};
+use log::{debug, warn};
+use rdkafka::{ClientConfig, Offset, TopicPartitionList};
+
+use crate::config::GROUP_ID;
+
+use super::consumer::{Consumer, DefaultContext, Result};
+use super::metadata::{is_internal_topic, Metadata};
+
+// Groups whose topics & consumers are remembered, the topics of the groups beyond are only known while they have members
+// & their consumers are created on every refresh
+const MAX_CACHED_GROUPS: usize = 1000;
+
+// Size & recent activity of a topic
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TopicActivity {
+    // messages retained in the topic, sum of HWM - LWM of the partitions
+    pub messages: i64,
+    // messages produced since the previous refresh, unknown on the first refresh
+    pub new_messages: Option<i64>,
+    // sum of the high watermarks of the partitions
+    high_watermarks: i64,
+}
+
+// Size & activity of the topics and lag of the consumer groups, refreshed in the background
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Activity {
+    topics: HashMap<String, TopicActivity>,
+    // lag of the groups summed across the partitions with committed offsets
+    group_lags: HashMap<String, i64>,
+    updated_at: Option<DateTime<Local>>,
+}
+
+impl Activity {
+    pub fn topic(&self, name: &str) -> Option<&TopicActivity> {
+        self.topics.get(name)
+    }
+
+    pub fn group_lag(&self, name: &str) -> Option<i64> {
+        self.group_lags.get(name).copied()
+    }
+
+    pub fn updated_at(&self) -> Option<DateTime<Local>> {
+        self.updated_at
+    }
+}
+
+// Fetches the activity with consumers of its own, so that the consumer of the TUI is not blocked
+pub struct ActivityFetcher {
+    consumer: Consumer<DefaultContext>,
+    client_config: ClientConfig,
+    context: DefaultContext,
+    // topics last assigned to the members of the groups, used once the group has no members
+    group_topics: HashMap<String, Vec<String>>,
+    // consumers under the groups, reused across refreshes
+    group_consumers: HashMap<String, Consumer<DefaultContext>>,
+    previous: Activity,
+}
+
+impl ActivityFetcher {
+    pub fn new(client_config: &ClientConfig, context: DefaultContext) -> Result<ActivityFetcher> {
+        Ok(ActivityFetcher {
+            consumer: Consumer::new(client_config, context.clone(), true)?,
+            client_config: client_config.clone(),
+            context,
+            group_topics: HashMap::new(),
+            group_consumers: HashMap::new(),
+            previous: Activity::default(),
+        })
+    }
+
+    // Fetch the watermarks of all partitions & the committed offsets of all groups
+    pub fn fetch(&mut self, metadata: &Metadata) -> Activity {
+        debug!("fetching activity ...");
+        let mut topics = HashMap::new();
+        let mut high_watermarks = HashMap::new();
+
+        for topic in metadata.topics() {
+            let mut activity = TopicActivity::default();
+
+            // offline partitions would only time out
+            for partition in topic.partitions().iter().filter(|p| !p.is_offline()) {
+                match self.consumer.fetch_watermarks(topic.name(), partition.id()) {
+                    Ok((low, high)) => {
+                        activity.messages += high - low;
+                        activity.high_watermarks += high;
+                        high_watermarks.insert((topic.name().to_string(), partition.id()), high);
+                    },
+                    Err(err) => debug!("unable to fetch watermarks for {}/{}: {}", topic.name(), partition.id(), err),
+                }
+            }
+
+            activity.new_messages = self.previous.topic(topic.name())
+                .map(|p| (activity.high_watermarks - p.high_watermarks).max(0));
+            topics.insert(topic.name().to_string(), activity);
+        }
+
+        let activity = Activity {
+            topics,
+            group_lags: self.fetch_group_lags(metadata, &high_watermarks),
+            updated_at: Some(Local::now()),
+        };
+
+        self.previous = activity.clone();
+        activity
+    }
+
+    // Lag of the consumer groups, groups without committed offsets have no lag
+    // committed offsets can only be fetched by a consumer under the group, so a consumer is kept per group
+    // & only the partitions of the topics assigned to the group are queried
+    fn fetch_group_lags(&mut self, metadata: &Metadata, high_watermarks: &HashMap<(String, i32), i64>) -> HashMap<String, i64> {
+        let groups = metadata.consumer_groups().iter()
+            .filter(|g| matches!(g.protocol_type(), "consumer" | ""))
+            .collect::<Vec<_>>();
+
+        // topics & consumers of the groups which no longer exist are dropped
+        let exists = |name: &String| groups.iter().any(|g| g.name() == name);
+        self.group_topics.retain(|name, _| exists(name));
+        self.group_consumers.retain(|name, _| exists(name));
+
+        let mut lags = HashMap::new();
+        for group in groups {
+            let assigned = group.assigned_topics();
+            let topics = if assigned.is_empty() {
+                // groups without members are skipped until their topics are known
+                match self.group_topics.get(group.name()) {
+                    Some(topics) => topics.clone(),
+                    None => continue,
+                }
+            } else {
+                if self.group_topics.len() < MAX_CACHED_GROUPS || self.group_topics.contains_key(group.name()) {
+                    self.group_topics.insert(group.name().to_string(), assigned.clone());
+                }
+                assigned
+            };
+
+            let mut partitions = TopicPartitionList::new();
+            for (topic, partition) in high_watermarks.keys().filter(|(t, _)| !is_internal_topic(t) && topics.contains(t)) {
+                partitions.add_partition(topic, *partition);
+            }
+
+            if partitions.count() == 0 {
+                continue;
+            }
+
+            if !self.group_consumers.contains_key(group.name()) && self.group_consumers.len() < MAX_CACHED_GROUPS {
+                let Some(consumer) = self.group_consumer(group.name()) else {
+                    continue;
+                };
+                self.group_consumers.insert(group.name().to_string(), consumer);
+            }
+
+            let uncached;
+            let consumer = match self.group_consumers.get(group.name()) {
+                Some(consumer) => consumer,
+                None => match self.group_consumer(group.name()) {
+                    Some(consumer) => {
+                        uncached = consumer;
+                        &uncached
+                    },
+                    None => continue,
+                },
+            };
+
+            let committed = match consumer.committed_offsets(&partitions) {
+                Ok(committed) => committed,
+                Err(err) => {
+                    debug!("unable to fetch the offsets of group {}: {}", group.name(), err);
+                    continue;
+                },
+            };
+
+            let partition_lags = committed.elements().iter()
+                .filter_map(|e| match e.offset() {
+                    Offset::Offset(offset) => high_watermarks.get(&(e.topic().to_string(), e.partition()))
+                        .map(|high| (high - offset).max(0)),
+                    _ => None,
+                })
+                .collect::<Vec<i64>>();
+
+            if !partition_lags.is_empty() {
+                lags.insert(group.name().to_string(), partition_lags.iter().sum());
+            }
+        }
+
+        lags
+    }
+
+    // Consumer under the group, it never joins the group or commits offsets
+    fn group_consumer(&self, group: &str) -> Option<Consumer<DefaultContext>> {
+        let mut config = self.client_config.clone();
+        config.set(GROUP_ID, group);
+        Consumer::new(&config, self.context.clone(), true)
+            .inspect_err(|err| warn!("unable to create consumer to fetch the offsets of group {}: {}", group, err))
+            .ok()
+    }
+}
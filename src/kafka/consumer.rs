@@ -1,4 +1,5 @@
-use std::{ collections::HashMap, error::Error, fmt::Display, time::Duration};
+use std::{ collections::HashMap, error::Error, fmt::Display, time::{Duration, Instant}};
+use base64::{engine::general_purpose::STANDARD, Engine};
 use crossbeam::channel::Sender;
 use log::debug;
 use rdkafka::{
@@ -10,7 +11,7 @@ use rdkafka::{
 use reqwest::blocking::Client as http_client;
 use serde::Deserialize;
 
-use crate::{config::Config, kafka::metadata::{ConsumerGroup, Metadata}};
+use crate::{config::{Config, ENABLE_AUTO_COMMIT, ENABLE_AUTO_OFFSET_STORE}, kafka::{activity::Activity, metadata::{ConsumerGroup, Metadata}}};
 
 pub type Result<T> = std::result::Result<T, ConsumerError>;
 
@@ -144,6 +145,16 @@ impl ClientContext for StatsContext {
 
 const DEFAULT_TIMEOUT_IN_SECS: Duration = Duration::from_secs(30);
 const DEFAULT_REFRESH_METADATA_IN_SECS: Duration = Duration::from_secs(30);
+// Reading a range fails when the position does not move for this long before the end, e.g. while the broker is down
+const RANGE_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Start or end of a range of a partition, the offset of a timestamp is looked up once the partition is assigned
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OffsetBound {
+    Offset(i64),
+    // epoch in milliseconds
+    Timestamp(i64),
+}
 
 // Wraps Kafka Consumer from the lib
 pub struct Consumer<T>
@@ -152,16 +163,26 @@ where T: ClientContext + ConsumerContext {
     default_timeout_in_secs: Timeout,
     pub refresh_metadata_in_secs: Duration,
     metadata: Metadata,
+    activity: Activity,
     stats: Statistics,
+    read_only: bool,
 }
  
 impl <T> Consumer<T> 
 where T: ClientContext + ConsumerContext 
 {
     // New Consumer
-    pub fn new(config: &ClientConfig, context: T) -> Result<Consumer<T>> {
+    // in read-only mode offset commits are disabled irrespective of the client config
+    pub fn new(config: &ClientConfig, context: T, read_only: bool) -> Result<Consumer<T>> {
+        let mut config = config.clone();
+        if read_only {
+            debug!("creating consumer in read-only mode");
+            config.set(ENABLE_AUTO_COMMIT, "false");
+            config.set(ENABLE_AUTO_OFFSET_STORE, "false");
+        }
+
         // Base Consumer
-        let base_consumer = BaseConsumer::from_config_and_context(config, context)?;
+        let base_consumer = BaseConsumer::from_config_and_context(&config, context)?;
 
         // Time out
         let default_timeout = Timeout::After(DEFAULT_TIMEOUT_IN_SECS);
@@ -171,7 +192,9 @@ where T: ClientContext + ConsumerContext
             default_timeout_in_secs: default_timeout,
             refresh_metadata_in_secs: DEFAULT_REFRESH_METADATA_IN_SECS,
             metadata: Metadata::new(),
+            activity: Activity::default(),
             stats: Statistics::default(),
+            read_only,
         };
 
         Ok(consumer)
@@ -196,6 +219,21 @@ where T: ClientContext + ConsumerContext
         &self.metadata
     }
 
+    // Update size & activity of the topics and lag of the groups
+    pub fn update_activity(&mut self, activity: Activity) {
+        self.activity = activity;
+    }
+
+    // Return Activity
+    pub fn activity(&self) -> &Activity {
+        &self.activity
+    }
+
+    // Whether the consumer was created in read-only mode
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
      pub fn fetch_groups(&self) -> Result<Vec<ConsumerGroup>>{
         debug!("fetching groups ...");
         let group_list = self.base_consumer.fetch_group_list(None, self.default_timeout_in_secs)?;
@@ -211,6 +249,12 @@ where T: ClientContext + ConsumerContext
         Ok(watermarks)
     }
 
+    // Offsets committed by the group of the consumer for the given partitions
+    pub fn committed_offsets(&self, partitions: &TopicPartitionList) -> Result<TopicPartitionList> {
+        let committed = self.base_consumer.committed_offsets(partitions.clone(), self.default_timeout_in_secs)?;
+        Ok(committed)
+    }
+
     // Update stats
     pub fn update_stats(&mut self, stats: Statistics) {
         self.stats = stats
@@ -304,6 +348,74 @@ where T: ClientContext + ConsumerContext
         Ok(())
     }
 
+    // Offset of the next message to be consumed from the partition, None until the partition is fetched
+    pub fn position(&self, topic: &str, partition: i32) -> Result<Option<i64>> {
+        let tpl = self.base_consumer.position()?;
+        Ok(tpl.find_partition(topic, partition)
+            .and_then(|e| e.offset().to_raw())
+            .filter(|o| *o >= 0))
+    }
+
+    // Offset of the bound in the assigned partition, offsets are clamped to the watermarks
+    // the high watermark is returned for a timestamp when every message is older than the timestamp
+    pub fn resolve_offset(&self, topic: &str, partition: i32, bound: OffsetBound, (low, high): (i64, i64)) -> Result<i64> {
+        match bound {
+            OffsetBound::Offset(offset) => Ok(offset.clamp(low, high)),
+            OffsetBound::Timestamp(timestamp) => Ok(self.offsets_for_timestamp(topic, partition, timestamp)?
+                .filter(|o| *o >= 0)
+                .unwrap_or(high)),
+        }
+    }
+
+    // Read the messages of the assigned partition from the start until the end (exclusive), or forever without an end
+    // read is called with every message & with None after every poll without a message, reading stops once it returns false
+    pub fn read_range(&self, topic: &str, partition: i32, start: i64, end: Option<i64>, poll_timeout: Duration,
+        mut read: impl FnMut(Option<&KafkaMessage>) -> bool) -> Result<()> {
+        if end.is_some_and(|e| start >= e) {
+            return Ok(());
+        }
+
+        self.seek(topic, partition, start)?;
+        let (mut position, mut moved_at) = (None, Instant::now());
+        loop {
+            let Some(message) = self.consume(poll_timeout, false)? else {
+                if !read(None) {
+                    return Ok(());
+                }
+
+                let Some(end) = end else {
+                    continue;
+                };
+
+                // the offsets before the end may never be returned, e.g. transaction markers or deleted messages
+                // polling continues until the position of the consumer moves past them
+                let current = self.position(topic, partition)?;
+                if current.is_some_and(|p| p >= end) {
+                    return Ok(());
+                }
+
+                if current != position {
+                    (position, moved_at) = (current, Instant::now());
+                } else if moved_at.elapsed() >= RANGE_IDLE_TIMEOUT {
+                    return Err(ConsumerError {
+                        message: format!("no messages received for {}s before the end offset {} of {}/{}, position {}", RANGE_IDLE_TIMEOUT.as_secs(),
+                            end, topic, partition, position.map(|p| p.to_string()).unwrap_or("-".to_string())),
+                    });
+                }
+                continue;
+            };
+
+            moved_at = Instant::now();
+            if end.is_some_and(|e| message.offset >= e) {
+                return Ok(());
+            }
+
+            if !read(Some(&message)) || end.is_some_and(|e| message.offset + 1 >= e) {
+                return Ok(());
+            }
+        }
+    }
+
     // return the offset for a specific parition & timestamp
     pub fn offsets_for_timestamp(&self, topic: &str, partition: i32, timestamp: i64) -> Result<Option<i64>> {
         let tpl = self.base_consumer.offsets_for_timestamp(timestamp, DEFAULT_TIMEOUT_IN_SECS)?;
@@ -317,13 +429,20 @@ where T: ClientContext + ConsumerContext
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct KafkaMessage {
     pub topic: String,
     pub partition: i32,
     pub offset: i64,
     pub key: Option<String>,
+    // key which is not valid UTF-8, kept as is
+    pub binary_key: Option<Vec<u8>>,
     pub headers: HashMap<String, String>,
+    // headers as they were produced, in order & along with repeated keys & null values
+    pub raw_headers: Vec<(String, Option<Vec<u8>>)>,
     pub payload: Option<String>,
+    // payload which is not valid UTF-8, kept as is
+    pub binary_payload: Option<Vec<u8>>,
     pub timestamp: Option<i64>,
 }
 
@@ -334,8 +453,13 @@ impl KafkaMessage {
             partition: msg.partition(),
             offset: msg.offset(),
             key: retrieve_key(msg),
+            binary_key: msg.key().filter(|k| std::str::from_utf8(k).is_err()).map(|k| k.to_vec()),
             payload: retrieve_payload(msg),
+            binary_payload: msg.payload().filter(|p| std::str::from_utf8(p).is_err()).map(|p| p.to_vec()),
             headers: retrieve_headers(msg),
+            raw_headers: msg.headers()
+                .map(|h| h.iter().map(|h| (h.key.to_string(), h.value.map(|v| v.to_vec()))).collect())
+                .unwrap_or_default(),
             timestamp: match msg.timestamp() {
                 rdkafka::message::Timestamp::NotAvailable => None,
                 rdkafka::message::Timestamp::CreateTime(t) => Some(t),
@@ -358,6 +482,70 @@ impl KafkaMessage {
     pub fn key_or_default(&self) -> String {
         return self.key.clone().unwrap_or("No key".to_string())
     }
+
+    // Key bytes as they were produced
+    pub fn raw_key(&self) -> Option<&[u8]> {
+        self.key.as_ref().map(|k| k.as_bytes()).or(self.binary_key.as_deref())
+    }
+
+    // Payload bytes as they were produced
+    pub fn raw_payload(&self) -> Option<&[u8]> {
+        self.payload.as_ref().map(|p| p.as_bytes()).or(self.binary_payload.as_deref())
+    }
+
+    // Message as JSON, payload is embedded as JSON if it is valid JSON
+    pub fn to_json(&self) -> serde_json::Value {
+        let payload = self.payload.as_ref()
+            .map(|p| serde_json::from_str::<serde_json::Value>(p).unwrap_or(serde_json::Value::String(p.clone())));
+
+        serde_json::json!({
+            "topic": self.topic,
+            "partition": self.partition,
+            "offset": self.offset,
+            "timestamp": self.timestamp,
+            "key": self.key,
+            "headers": self.headers,
+            "payload": payload,
+        })
+    }
+
+    // Message as JSON keeping the key, the headers & the payload as they were produced, e.g. to export it
+    // bytes which are not valid UTF-8 are base64 encoded in the fields suffixed with _base64, e.g. payload_base64
+    pub fn to_raw_json(&self) -> serde_json::Value {
+        let mut json = serde_json::Map::new();
+        json.insert("topic".to_string(), self.topic.clone().into());
+        json.insert("partition".to_string(), self.partition.into());
+        json.insert("offset".to_string(), self.offset.into());
+        json.insert("timestamp".to_string(), self.timestamp.into());
+        insert_bytes(&mut json, "key", self.raw_key());
+        json.insert("headers".to_string(), self.raw_headers_json());
+        insert_bytes(&mut json, "payload", self.raw_payload());
+        serde_json::Value::Object(json)
+    }
+
+    // Headers as a JSON array of keys & values, in the order they were produced
+    pub fn raw_headers_json(&self) -> serde_json::Value {
+        self.raw_headers.iter()
+            .map(|(key, value)| {
+                let mut header = serde_json::Map::new();
+                header.insert("key".to_string(), key.clone().into());
+                insert_bytes(&mut header, "value", value.as_deref());
+                serde_json::Value::Object(header)
+            })
+            .collect()
+    }
+}
+
+// Bytes as a string, or null along with the base64 encoded bytes if they are not valid UTF-8
+fn insert_bytes(json: &mut serde_json::Map<String, serde_json::Value>, field: &str, bytes: Option<&[u8]>) {
+    match bytes.map(|b| (b, std::str::from_utf8(b))) {
+        Some((_, Ok(value))) => json.insert(field.to_string(), value.into()),
+        Some((bytes, Err(_))) => {
+            json.insert(field.to_string(), serde_json::Value::Null);
+            json.insert(format!("{}_base64", field), STANDARD.encode(bytes).into())
+        },
+        None => json.insert(field.to_string(), serde_json::Value::Null),
+    };
 }
 
 // retrieve key from original kafka message
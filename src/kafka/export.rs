@@ -0,0 +1,359 @@
+use std::{fs::File, io::{BufWriter, Write}, path::Path, sync::{atomic::{AtomicBool, Ordering}, Arc}, thread, time::Duration};
+
+use log::{debug, info};
+use parking_lot::Mutex;
+use rdkafka::ClientConfig;
+use strum::Display;
+
+use super::consumer::{Consumer, DefaultContext, KafkaMessage, OffsetBound};
+
+// Messages are polled for this long before checking whether the export is cancelled
+const EXPORT_POLL_TIMEOUT: Duration = Duration::from_secs(1);
+// Start of the raw format, followed by the records
+const RAW_MAGIC: &[u8] = b"KAFKA2I1";
+const CSV_HEADER: &str = "topic,partition,offset,timestamp,key,headers,payload";
+
+// Format of the exported file, picked by the extension of the file
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+pub enum ExportFormat {
+    #[strum(serialize = "JSONL")]
+    Jsonl,
+    #[strum(serialize = "CSV")]
+    Csv,
+    #[strum(serialize = "raw")]
+    Raw,
+}
+
+impl ExportFormat {
+    pub fn from_path(path: &str) -> Option<ExportFormat> {
+        match Path::new(path).extension()?.to_str()?.to_lowercase().as_str() {
+            "jsonl" | "json" => Some(ExportFormat::Jsonl),
+            "csv" => Some(ExportFormat::Csv),
+            "bin" | "raw" => Some(ExportFormat::Raw),
+            _ => None,
+        }
+    }
+}
+
+// Messages of a partition from the start until the end of the range, the message at the end is not exported
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportRange {
+    pub topic: String,
+    pub partition: i32,
+    pub from: OffsetBound,
+    pub to: OffsetBound,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportStatus {
+    Running,
+    Completed,
+    Cancelled,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportProgress {
+    pub exported: i64,
+    // messages in the range, unknown until the range is resolved
+    pub total: Option<i64>,
+    pub status: ExportStatus,
+}
+
+// Export running in the background, cancelled at the next message
+pub struct Export {
+    pub range: ExportRange,
+    pub path: String,
+    pub format: ExportFormat,
+    progress: Arc<Mutex<ExportProgress>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Export {
+    pub fn progress(&self) -> ExportProgress {
+        self.progress.lock().clone()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+// Exports messages with a consumer of its own, so that the consumer of the TUI is not blocked
+pub struct Exporter {
+    client_config: ClientConfig,
+    context: DefaultContext,
+}
+
+impl Exporter {
+    pub fn new(client_config: &ClientConfig, context: DefaultContext) -> Exporter {
+        Exporter {
+            client_config: client_config.clone(),
+            context,
+        }
+    }
+
+    // Export the range to the file on a thread of its own
+    pub fn start(&self, range: ExportRange, path: &str, format: ExportFormat) -> Export {
+        let progress = Arc::new(Mutex::new(ExportProgress { exported: 0, total: None, status: ExportStatus::Running }));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let (thread_progress, thread_cancelled) = (progress.clone(), cancelled.clone());
+        let (client_config, context) = (self.client_config.clone(), self.context.clone());
+        let (thread_range, thread_path) = (range.clone(), path.to_string());
+        thread::spawn(move || {
+            let status = match export(&client_config, context, &thread_range, &thread_path, format, &thread_progress, &thread_cancelled) {
+                Ok(_) if thread_cancelled.load(Ordering::Relaxed) => ExportStatus::Cancelled,
+                Ok(_) => ExportStatus::Completed,
+                Err(err) => ExportStatus::Failed(err),
+            };
+
+            info!("export of {}/{} to {} finished: {:?}", thread_range.topic, thread_range.partition, thread_path, status);
+            thread_progress.lock().status = status;
+        });
+
+        Export { range, path: path.to_string(), format, progress, cancelled }
+    }
+}
+
+fn export(client_config: &ClientConfig, context: DefaultContext, range: &ExportRange, path: &str, format: ExportFormat,
+    progress: &Mutex<ExportProgress>, cancelled: &AtomicBool) -> Result<(), String> {
+    let (topic, partition) = (range.topic.as_str(), range.partition);
+    let consumer = Consumer::new(client_config, context, true).map_err(|err| err.to_string())?;
+
+    // offsets for the timestamps are looked up among the assigned partitions
+    consumer.assign(topic, partition).map_err(|err| err.to_string())?;
+    let watermarks = consumer.fetch_watermarks(topic, partition).map_err(|err| err.to_string())?;
+    let start = consumer.resolve_offset(topic, partition, range.from, watermarks).map_err(|err| err.to_string())?;
+    let end = consumer.resolve_offset(topic, partition, range.to, watermarks).map_err(|err| err.to_string())?;
+    progress.lock().total = Some((end - start).max(0));
+    debug!("exporting offsets {} to {} of {}/{} to {}", start, end, topic, partition, path);
+
+    let file = File::create(path).map_err(|err| format!("unable to create {}: {}", path, err))?;
+    let mut writer = BufWriter::new(file);
+    let write_error = |err: std::io::Error| format!("unable to write to {}: {}", path, err);
+    match format {
+        ExportFormat::Csv => writeln!(writer, "{}", CSV_HEADER).map_err(write_error)?,
+        ExportFormat::Raw => writer.write_all(RAW_MAGIC).map_err(write_error)?,
+        ExportFormat::Jsonl => (),
+    }
+
+    // messages are written until the end of the range or until the export is cancelled, checked after every poll
+    let mut written = Ok(());
+    consumer.read_range(topic, partition, start, Some(end), EXPORT_POLL_TIMEOUT, |message| {
+        let Some(message) = message else {
+            return !cancelled.load(Ordering::Relaxed);
+        };
+
+        if let Err(err) = write_message(&mut writer, message, format) {
+            written = Err(err);
+            return false;
+        }
+
+        progress.lock().exported += 1;
+        !cancelled.load(Ordering::Relaxed)
+    }).map_err(|err| err.to_string())?;
+
+    written.and_then(|_| writer.flush()).map_err(write_error)
+}
+
+fn write_message(writer: &mut impl Write, message: &KafkaMessage, format: ExportFormat) -> std::io::Result<()> {
+    match format {
+        ExportFormat::Jsonl => writeln!(writer, "{}", message.to_raw_json()),
+        ExportFormat::Csv => {
+            let headers = message.raw_headers_json().to_string();
+            let fields = [
+                message.topic.clone(),
+                message.partition.to_string(),
+                message.offset.to_string(),
+                message.timestamp.map(|t| t.to_string()).unwrap_or_default(),
+                message.raw_key().map(|k| String::from_utf8_lossy(k).to_string()).unwrap_or_default(),
+                headers,
+                message.raw_payload().map(|p| String::from_utf8_lossy(p).to_string()).unwrap_or_default(),
+            ];
+
+            writeln!(writer, "{}", fields.iter().map(|f| csv_field(f)).collect::<Vec<String>>().join(","))
+        },
+        ExportFormat::Raw => write_raw(writer, message),
+    }
+}
+
+// Fields with separators, quotes or line breaks are quoted
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// Record in the raw format, numbers are big-endian
+// offset (i64), timestamp (i64, -1 if missing), key & payload (i32 length, -1 if missing, followed by the bytes)
+// and the number of headers (i32) followed by the key & value of every header in the order they were produced
+// (i32 length followed by the bytes, -1 for a null value)
+fn write_raw(writer: &mut impl Write, message: &KafkaMessage) -> std::io::Result<()> {
+    writer.write_all(&message.offset.to_be_bytes())?;
+    writer.write_all(&message.timestamp.unwrap_or(-1).to_be_bytes())?;
+    write_bytes(writer, message.raw_key())?;
+    write_bytes(writer, message.raw_payload())?;
+
+    writer.write_all(&(message.raw_headers.len() as i32).to_be_bytes())?;
+    for (key, value) in &message.raw_headers {
+        write_bytes(writer, Some(key.as_bytes()))?;
+        write_bytes(writer, value.as_deref())?;
+    }
+
+    Ok(())
+}
+
+fn write_bytes(writer: &mut impl Write, bytes: Option<&[u8]>) -> std::io::Result<()> {
+    match bytes {
+        Some(bytes) => {
+            writer.write_all(&(bytes.len() as i32).to_be_bytes())?;
+            writer.write_all(bytes)
+        },
+        None => writer.write_all(&(-1i32).to_be_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn message(key: Option<&str>, payload: Option<&str>, timestamp: Option<i64>) -> KafkaMessage {
+        KafkaMessage {
+            topic: "orders".to_string(),
+            partition: 3,
+            offset: 42,
+            key: key.map(|k| k.to_string()),
+            binary_key: None,
+            headers: HashMap::new(),
+            raw_headers: vec![],
+            payload: payload.map(|p| p.to_string()),
+            binary_payload: None,
+            timestamp,
+        }
+    }
+
+    // reads the bytes written by write_bytes, moving the cursor past them
+    fn read_bytes(bytes: &[u8], cursor: &mut usize) -> Option<Vec<u8>> {
+        let len = i32::from_be_bytes(bytes[*cursor..*cursor + 4].try_into().unwrap());
+        *cursor += 4;
+        if len < 0 {
+            return None;
+        }
+
+        let read = bytes[*cursor..*cursor + len as usize].to_vec();
+        *cursor += len as usize;
+        Some(read)
+    }
+
+    fn read_i64(bytes: &[u8], cursor: &mut usize) -> i64 {
+        let read = i64::from_be_bytes(bytes[*cursor..*cursor + 8].try_into().unwrap());
+        *cursor += 8;
+        read
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(ExportFormat::from_path("orders.jsonl"), Some(ExportFormat::Jsonl));
+        assert_eq!(ExportFormat::from_path("orders.json"), Some(ExportFormat::Jsonl));
+        assert_eq!(ExportFormat::from_path("/tmp/orders.CSV"), Some(ExportFormat::Csv));
+        assert_eq!(ExportFormat::from_path("orders.Bin"), Some(ExportFormat::Raw));
+        assert_eq!(ExportFormat::from_path("orders.raw"), Some(ExportFormat::Raw));
+    }
+
+    #[test]
+    fn format_from_path_with_unknown_extension() {
+        assert_eq!(ExportFormat::from_path("orders.txt"), None);
+        assert_eq!(ExportFormat::from_path("orders"), None);
+        assert_eq!(ExportFormat::from_path("orders.csv.gz"), None);
+    }
+
+    #[test]
+    fn csv_field_is_quoted_when_needed() {
+        assert_eq!(csv_field("order-1"), "order-1");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field("line\rbreak"), "\"line\rbreak\"");
+    }
+
+    #[test]
+    fn raw_export_starts_with_magic() {
+        let mut bytes = RAW_MAGIC.to_vec();
+        write_raw(&mut bytes, &message(Some("order-1"), Some("{}"), Some(1700000000000))).unwrap();
+
+        assert_eq!(&bytes[..8], b"KAFKA2I1");
+        assert_eq!(i64::from_be_bytes(bytes[8..16].try_into().unwrap()), 42);
+    }
+
+    #[test]
+    fn raw_record_of_message() {
+        let mut message = message(Some("order-1"), Some("{}"), Some(1700000000000));
+        message.raw_headers = vec![
+            ("trace".to_string(), Some(b"abc".to_vec())),
+            ("retry".to_string(), None),
+            ("trace".to_string(), Some(b"def".to_vec())),
+        ];
+        let mut bytes = vec![];
+        write_raw(&mut bytes, &message).unwrap();
+
+        let mut cursor = 0;
+        assert_eq!(read_i64(&bytes, &mut cursor), 42);
+        assert_eq!(read_i64(&bytes, &mut cursor), 1700000000000);
+        assert_eq!(read_bytes(&bytes, &mut cursor), Some(b"order-1".to_vec()));
+        assert_eq!(read_bytes(&bytes, &mut cursor), Some(b"{}".to_vec()));
+
+        assert_eq!(i32::from_be_bytes(bytes[cursor..cursor + 4].try_into().unwrap()), 3);
+        cursor += 4;
+        let headers: Vec<_> = (0..3)
+            .map(|_| (read_bytes(&bytes, &mut cursor), read_bytes(&bytes, &mut cursor)))
+            .collect();
+        assert_eq!(headers, vec![
+            (Some(b"trace".to_vec()), Some(b"abc".to_vec())),
+            (Some(b"retry".to_vec()), None),
+            (Some(b"trace".to_vec()), Some(b"def".to_vec())),
+        ]);
+        assert_eq!(cursor, bytes.len());
+    }
+
+    #[test]
+    fn raw_record_of_message_without_key_payload_or_timestamp() {
+        let mut bytes = vec![];
+        write_raw(&mut bytes, &message(None, None, None)).unwrap();
+
+        let mut cursor = 0;
+        assert_eq!(read_i64(&bytes, &mut cursor), 42);
+        assert_eq!(read_i64(&bytes, &mut cursor), -1);
+        assert_eq!(read_bytes(&bytes, &mut cursor), None);
+        assert_eq!(read_bytes(&bytes, &mut cursor), None);
+        assert_eq!(&bytes[cursor..], &0i32.to_be_bytes());
+    }
+
+    #[test]
+    fn raw_record_keeps_binary_payload() {
+        let payload = vec![0xff, 0x00, 0xfe, b'a', 0x80];
+        let mut message = message(Some("order-1"), None, Some(1700000000000));
+        message.binary_payload = Some(payload.clone());
+        let mut bytes = vec![];
+        write_raw(&mut bytes, &message).unwrap();
+
+        let mut cursor = 16;
+        assert_eq!(read_bytes(&bytes, &mut cursor), Some(b"order-1".to_vec()));
+        assert_eq!(read_bytes(&bytes, &mut cursor), Some(payload));
+    }
+
+    #[test]
+    fn write_bytes_prefixes_the_length() {
+        let mut bytes = vec![];
+        write_bytes(&mut bytes, Some(b"abc")).unwrap();
+        write_bytes(&mut bytes, Some(b"")).unwrap();
+        write_bytes(&mut bytes, None).unwrap();
+
+        assert_eq!(bytes, [&3i32.to_be_bytes()[..], b"abc", &0i32.to_be_bytes(), &(-1i32).to_be_bytes()].concat());
+    }
+}
@@ -2,7 +2,10 @@ use rdkafka::groups::{GroupInfo, GroupMemberInfo};
 use rdkafka::metadata::{Metadata as KafkaMetadata, MetadataTopic, MetadataPartition, MetadataBroker};
 use rdkafka::statistics::{Broker as StatsBroker, Topic as StatsTopic, Partition as StatsPartition};
 
-#[derive(Debug, Clone)]
+// Suffixes of the topics created by Kafka Streams applications, e.g. app-store-changelog
+const STREAM_TOPIC_SUFFIXES: [&str; 2] = ["-changelog", "-repartition"];
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Metadata {
     brokers: Vec<Broker>,
     topics: Vec<Topic>,
@@ -48,6 +51,13 @@ impl Metadata {
         topics
     }
 
+    // Topics list without the internal or the stream processing topics if they are to be hidden
+    pub fn visible_topics_list(&self, hide_internal: bool, hide_streams: bool) -> Vec<String> {
+        self.topics_list().into_iter()
+            .filter(|t| (!hide_internal || !is_internal_topic(t)) && (!hide_streams || !is_stream_topic(t)))
+            .collect()
+    }
+
     pub fn consumer_group_lists(&self) -> Vec<String> {
         let mut cgs = self.consumer_groups.iter()
             .map(|g| g.name.clone())
@@ -103,9 +113,25 @@ impl Metadata {
         self.topics.iter().flat_map(|t| t.partitions().iter().filter(|p| p.leader == broker_id)).count()
     }
 
+    pub fn no_of_replicas_for_broker(&self, broker_id: i32) -> usize {
+        self.topics.iter().flat_map(|t| t.partitions().iter().filter(|p| p.replicas.contains(&broker_id))).count()
+    }
+
+    pub fn brokers(&self) -> &[Broker] {
+        &self.brokers
+    }
+
+    pub fn topics(&self) -> &[Topic] {
+        &self.topics
+    }
+
+    pub fn consumer_groups(&self) -> &[ConsumerGroup] {
+        &self.consumer_groups
+    }
+
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Broker {
     id: i32,
     name: String,
@@ -150,7 +176,17 @@ impl From<&StatsBroker> for Broker {
     }
 }
 
-#[derive(Debug, Clone)]
+// Internal topics of the brokers & the platform, e.g. __consumer_offsets, _schemas, _confluent-metrics
+pub fn is_internal_topic(name: &str) -> bool {
+    name.starts_with("__") || name == "_schemas" || name.starts_with("_confluent")
+}
+
+// Changelog & repartition topics of the stream processing applications
+pub fn is_stream_topic(name: &str) -> bool {
+    STREAM_TOPIC_SUFFIXES.iter().any(|s| name.ends_with(s))
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Topic {
     name: String,
     partitions: Vec<Partition>,
@@ -204,7 +240,7 @@ impl From<&StatsTopic> for Topic {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Partition {
     id: i32,
     leader: i32,
@@ -228,6 +264,16 @@ impl Partition {
     pub fn replicas(&self) -> Vec<i32> {
         self.replicas.clone()
     }
+
+    // fewer replicas are in sync than assigned
+    pub fn is_under_replicated(&self) -> bool {
+        self.isr.len() < self.replicas.len()
+    }
+
+    // partition has no leader
+    pub fn is_offline(&self) -> bool {
+        self.leader < 0
+    }
 }
 
 impl From<&MetadataPartition> for Partition {
@@ -252,15 +298,17 @@ impl From<&StatsPartition> for Partition {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ConsumerGroup {
     name: String,
     members: Vec<ConsumerGroupMember>,
     state: String,
+    protocol_type: String,
+    protocol: String,
 }
 
 impl ConsumerGroup {
-    fn name(&self) -> &str {
+    pub fn name(&self) -> &str {
         &self.name
     }
 
@@ -271,6 +319,28 @@ impl ConsumerGroup {
     pub fn members_count(&self) -> usize {
         self.members.len()
     }
+
+    pub fn members(&self) -> &[ConsumerGroupMember] {
+        &self.members
+    }
+
+    pub fn protocol_type(&self) -> &str {
+        &self.protocol_type
+    }
+
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    // Topics assigned to the members of the group, empty if the group has no members
+    pub fn assigned_topics(&self) -> Vec<String> {
+        let mut topics = self.members.iter()
+            .flat_map(|m| m.topics.iter().cloned())
+            .collect::<Vec<String>>();
+        topics.sort();
+        topics.dedup();
+        topics
+    }
 }
 
 impl From<&GroupInfo> for ConsumerGroup {
@@ -282,20 +352,110 @@ impl From<&GroupInfo> for ConsumerGroup {
         ConsumerGroup {
             name: value.name().to_string(),
             members,
-            state: value.state().to_string()
+            state: value.state().to_string(),
+            protocol_type: value.protocol_type().to_string(),
+            protocol: value.protocol().to_string(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ConsumerGroupMember {
     id: String,
+    client_id: String,
+    client_host: String,
+    // topics of the partitions assigned to the member
+    topics: Vec<String>,
+}
+
+impl ConsumerGroupMember {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    pub fn client_host(&self) -> &str {
+        &self.client_host
+    }
 }
 
 impl From<&GroupMemberInfo> for ConsumerGroupMember {
     fn from(value: &GroupMemberInfo) -> Self {
         ConsumerGroupMember {
-            id: value.id().to_string()
+            id: value.id().to_string(),
+            client_id: value.client_id().to_string(),
+            client_host: value.client_host().to_string(),
+            topics: value.assignment().map(assigned_topics).unwrap_or_default(),
         }
     }
-}
\ No newline at end of file
+}
+
+// Topics of the member assignment of the consumer protocol
+// version (i16), topics (i32 count of topic name as i16 length & bytes, followed by i32 count of partitions as i32) & user data
+// assignments of other protocols, e.g. connect, are not parsed & return no topics
+fn assigned_topics(assignment: &[u8]) -> Vec<String> {
+    parse_assignment(assignment).unwrap_or_default()
+}
+
+fn parse_assignment(mut bytes: &[u8]) -> Option<Vec<String>> {
+    take(&mut bytes, 2)?;
+    let count = i32::from_be_bytes(take(&mut bytes, 4)?.try_into().ok()?);
+
+    let mut topics = vec![];
+    for _ in 0..count {
+        let len = i16::from_be_bytes(take(&mut bytes, 2)?.try_into().ok()?);
+        let name = std::str::from_utf8(take(&mut bytes, usize::try_from(len).ok()?)?).ok()?;
+        let partitions = i32::from_be_bytes(take(&mut bytes, 4)?.try_into().ok()?);
+        take(&mut bytes, usize::try_from(partitions).ok()? * 4)?;
+        topics.push(name.to_string());
+    }
+
+    Some(topics)
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    let (taken, rest) = (bytes.get(..len)?, bytes.get(len..)?);
+    *bytes = rest;
+    Some(taken)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // assignment of the consumer protocol with the given topics & partitions, followed by empty user data
+    fn assignment(topics: &[(&str, &[i32])]) -> Vec<u8> {
+        let mut bytes = 0i16.to_be_bytes().to_vec();
+        bytes.extend((topics.len() as i32).to_be_bytes());
+        for (topic, partitions) in topics {
+            bytes.extend((topic.len() as i16).to_be_bytes());
+            bytes.extend(topic.as_bytes());
+            bytes.extend((partitions.len() as i32).to_be_bytes());
+            for partition in partitions.iter() {
+                bytes.extend(partition.to_be_bytes());
+            }
+        }
+        bytes.extend((-1i32).to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn assigned_topics_of_consumer_assignment() {
+        let bytes = assignment(&[("orders", &[0, 1, 2]), ("payments", &[])]);
+        assert_eq!(assigned_topics(&bytes), vec!["orders", "payments"]);
+    }
+
+    #[test]
+    fn assigned_topics_of_empty_assignment() {
+        assert!(assigned_topics(&assignment(&[])).is_empty());
+        assert!(assigned_topics(&[]).is_empty());
+    }
+
+    #[test]
+    fn assigned_topics_of_truncated_assignment() {
+        let bytes = assignment(&[("orders", &[0, 1, 2])]);
+        assert!(assigned_topics(&bytes[..bytes.len() - 10]).is_empty());
+    }
+}
@@ -2,3 +2,5 @@ pub mod consumer;
 pub mod producer;
 pub mod metadata;
 pub mod stats;
+pub mod activity;
+pub mod export;
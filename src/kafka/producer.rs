@@ -0,0 +1,115 @@
+use std::{collections::HashMap, error::Error, fmt::Display, time::Duration};
+
+use futures::executor::block_on;
+use log::debug;
+use rdkafka::{
+    config::FromClientConfigAndContext, error::KafkaError, message::{Header, OwnedHeaders},
+    producer::{DeliveryFuture, FutureProducer, FutureRecord, Producer as KafkaProducer}, types::RDKafkaErrorCode, ClientConfig, ClientContext
+};
+
+use crate::config::{ENABLE_AUTO_COMMIT, ENABLE_AUTO_OFFSET_STORE, GROUP_ID};
+
+pub type Result<T> = std::result::Result<T, ProducerError>;
+
+const DEFAULT_TIMEOUT_IN_SECS: Duration = Duration::from_secs(30);
+// wait for the local queue to drain when it is full
+const QUEUE_FULL_BACKOFF_IN_MS: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone)]
+pub struct ProducerError {
+    message: String,
+}
+
+impl Display for ProducerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for ProducerError {}
+
+impl From<KafkaError> for ProducerError {
+    fn from(value: KafkaError) -> Self {
+        ProducerError {
+            message: value.to_string()
+        }
+    }
+}
+
+// Record to produce, the partition is picked by the partitioner when not given
+#[derive(Debug, Clone, Default)]
+pub struct ProducerRecord {
+    pub key: Option<String>,
+    pub headers: HashMap<String, String>,
+    pub payload: Option<String>,
+    pub partition: Option<i32>,
+}
+
+// Wraps Kafka Producer from the lib
+pub struct Producer<T>
+where T: ClientContext + 'static {
+    future_producer: FutureProducer<T>,
+}
+
+impl <T> Producer<T>
+where T: ClientContext + 'static
+{
+    // New Producer
+    // consumer only params of the client config are dropped as the lib warns about them
+    pub fn new(config: &ClientConfig, context: T) -> Result<Producer<T>> {
+        let mut config = config.clone();
+        config.remove(GROUP_ID);
+        config.remove(ENABLE_AUTO_COMMIT);
+        config.remove(ENABLE_AUTO_OFFSET_STORE);
+
+        let future_producer = FutureProducer::from_config_and_context(&config, context)?;
+        Ok(Producer { future_producer })
+    }
+
+    // Enqueue the record, the returned future completes once the record is delivered or fails
+    // waits for the local queue to drain while it is full
+    pub fn send(&self, topic: &str, record: &ProducerRecord) -> Result<DeliveryFuture> {
+        let mut headers = OwnedHeaders::new();
+        for (key, value) in &record.headers {
+            headers = headers.insert(Header { key, value: Some(value) });
+        }
+
+        let mut future_record: FutureRecord<String, String> = FutureRecord::to(topic).headers(headers);
+        if let Some(key) = &record.key {
+            future_record = future_record.key(key);
+        }
+        if let Some(payload) = &record.payload {
+            future_record = future_record.payload(payload);
+        }
+        if let Some(partition) = record.partition {
+            future_record = future_record.partition(partition);
+        }
+
+        loop {
+            match self.future_producer.send_result(future_record) {
+                Ok(delivery) => return Ok(delivery),
+                Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), returned)) => {
+                    debug!("producer queue is full, retrying ...");
+                    future_record = returned;
+                    std::thread::sleep(QUEUE_FULL_BACKOFF_IN_MS);
+                },
+                Err((err, _)) => return Err(err.into()),
+            }
+        }
+    }
+
+    // Wait for the enqueued records to be delivered
+    pub fn flush(&self) -> Result<()> {
+        self.future_producer.flush(DEFAULT_TIMEOUT_IN_SECS)?;
+        Ok(())
+    }
+}
+
+// Wait for the record to be delivered, returns the partition & the offset it was delivered at
+pub fn await_delivery(delivery: DeliveryFuture) -> Result<(i32, i64)> {
+    match block_on(delivery) {
+        Ok(Ok(delivery)) => Ok((delivery.partition, delivery.offset)),
+        Ok(Err((err, _))) => Err(ProducerError { message: format!("delivery failed: {}", err) }),
+        Err(_) => Err(ProducerError { message: "delivery failed: producer was dropped".to_string() }),
+    }
+}
@@ -1,12 +1,289 @@
+use std::{collections::VecDeque, error::Error, path::PathBuf, sync::Arc};
+
 use flexi_logger::{
-    opt_format, FileSpec, Logger, LoggerHandle
+    filter::{LogLineFilter, LogLineWriter}, opt_format, writers::LogWriter, Age, Cleanup, Criterion, DeferredNow, FileSpec, Logger, LoggerHandle, Naming
 };
+use log::{Level, Record};
+use parking_lot::Mutex;
+
+use crate::config::{self, Config};
+
+// Number of records kept in memory for the log pane
+const LOG_BUFFER_SIZE: usize = 5000;
+
+const LOG_DIR: &str = "logs";
+const LOG_BASENAME: &str = "kafka2i";
+const BYTES_IN_MB: u64 = 1024 * 1024;
+
+// Replacement for the redacted values
+const REDACTED: &str = "***";
+// Values of the keys containing these are redacted, e.g. sasl.password=..., "access_token":"..."
+const SECRET_KEYS: [&str; 6] = ["password", "secret", "access_token", "refresh_token", "id_token", "api_key"];
+// Values following these are redacted, e.g. Authorization: Bearer ...
+const SECRET_SCHEMES: [&str; 1] = ["bearer"];
+
+// Starts logging to the rotated log files & the in-memory buffer for the log pane
+pub fn initiate(config: &Config) -> Result<(LoggerHandle, LogBuffer), Box<dyn Error>> {
+    let log_buffer = LogBuffer::default();
 
-pub fn initiate() -> LoggerHandle {
-    let _logger = Logger::try_with_env().unwrap()
+    let logger = Logger::try_with_str(config.log_level.to_string())?
         .format(opt_format)
-        .log_to_file(FileSpec::default().directory("logs"))
-        .start().unwrap();
+        .filter(Box::new(Redactor::new(config)));
+
+    // logs are still written to the buffer when file logging is disabled
+    let logger = if config.no_log_file {
+        logger.log_to_writer(Box::new(log_buffer.clone()))
+    } else {
+        let cleanup = match config.log_keep_days {
+            Some(days) => Cleanup::KeepForDays(days),
+            None => Cleanup::KeepLogFiles(config.log_keep_files),
+        };
+
+        // a single log file is appended to across sessions & rotated daily or once it grows beyond the max size
+        logger.log_to_file_and_writer(FileSpec::default().directory(log_dir(config)).basename(LOG_BASENAME).suppress_timestamp(), Box::new(log_buffer.clone()))
+            .append()
+            .rotate(Criterion::AgeOrSize(Age::Day, config.log_max_size_mb * BYTES_IN_MB), Naming::Timestamps, cleanup)
+    };
+
+    Ok((logger.start()?, log_buffer))
+}
+
+// Directory for the log files, e.g. ~/.local/state/kafka2i/logs
+// falls back to logs in the working directory on platforms without a state directory
+fn log_dir(config: &Config) -> PathBuf {
+    match &config.log_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => config::state_dir()
+            .map(|d| d.join(LOG_DIR))
+            .unwrap_or(PathBuf::from(LOG_DIR)),
+    }
+}
+
+// Single log record kept in the log buffer
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub timestamp: String,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+// In-memory ring buffer of the latest log records, shared between the logger and the log pane
+#[derive(Debug, Clone, Default)]
+pub struct LogBuffer {
+    inner: Arc<Mutex<LogBufferInner>>,
+}
+
+#[derive(Debug, Default)]
+struct LogBufferInner {
+    records: VecDeque<LogRecord>,
+    // total number of records written, used to detect new records
+    total: u64,
+}
+
+impl LogBuffer {
+    // Total number of records written so far
+    pub fn total(&self) -> u64 {
+        self.inner.lock().total
+    }
+
+    // Snapshot of the records currently in the buffer, oldest first
+    pub fn records(&self) -> Vec<LogRecord> {
+        self.inner.lock().records.iter().cloned().collect()
+    }
+}
+
+impl LogWriter for LogBuffer {
+    fn write(&self, now: &mut DeferredNow, record: &Record) -> std::io::Result<()> {
+        let log_record = LogRecord {
+            timestamp: now.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            level: record.level(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+
+        let mut inner = self.inner.lock();
+        if inner.records.len() == LOG_BUFFER_SIZE {
+            inner.records.pop_front();
+        }
+        inner.records.push_back(log_record);
+        inner.total += 1;
+
+        Ok(())
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// Redacts secrets from the log records before they are written to the log files & the buffer
+struct Redactor {
+    // secrets passed in the config, these are redacted wherever they appear
+    secrets: Vec<String>,
+}
+
+impl Redactor {
+    fn new(config: &Config) -> Redactor {
+        let secrets = [&config.sasl_password, &config.oauth_client_secret, &config.ssl_keystore_password, &config.ssl_client_key_password]
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .cloned()
+            .collect();
+
+        Redactor { secrets }
+    }
+}
+
+impl LogLineFilter for Redactor {
+    fn write(&self, now: &mut DeferredNow, record: &Record, log_line_writer: &dyn LogLineWriter) -> std::io::Result<()> {
+        let message = record.args().to_string();
+        let redacted = redact(&message, &self.secrets);
+        if redacted == message {
+            return log_line_writer.write(now, record);
+        }
+
+        log_line_writer.write(now, &Record::builder()
+            .args(format_args!("{}", redacted))
+            .level(record.level())
+            .target(record.target())
+            .module_path(record.module_path())
+            .file(record.file())
+            .line(record.line())
+            .build())
+    }
+}
+
+fn redact(message: &str, secrets: &[String]) -> String {
+    let mut message = message.to_string();
+    for secret in secrets {
+        message = message.replace(secret.as_str(), REDACTED);
+    }
+
+    redact_values(&message)
+}
+
+// Redacts the values of the secret keys & schemes, the keys are matched case insensitively
+fn redact_values(message: &str) -> String {
+    // ascii lowercase keeps the byte positions intact
+    let lower = message.to_ascii_lowercase();
+    let bytes = message.as_bytes();
+    let mut redacted = String::with_capacity(message.len());
+    let mut copied = 0;
+    let mut pos = 0;
+
+    while let Some((key_start, key, is_scheme)) = next_secret_key(&lower, pos) {
+        let mut idx = key_start + key.len();
+        pos = idx;
+
+        if is_scheme {
+            // scheme is a separate word followed by whitespace & the credentials, e.g. not oauthbearer
+            let word_start = key_start == 0 || !bytes[key_start - 1].is_ascii_alphanumeric();
+            if !word_start || !bytes.get(idx).is_some_and(u8::is_ascii_whitespace) {
+                continue;
+            }
+        } else {
+            // rest of the key, e.g. sasl.password.file or "password"
+            while idx < bytes.len() && (bytes[idx].is_ascii_alphanumeric() || b"._-\"'".contains(&bytes[idx])) {
+                idx += 1;
+            }
+            while idx < bytes.len() && bytes[idx] == b' ' {
+                idx += 1;
+            }
+            if !bytes.get(idx).is_some_and(|b| *b == b'=' || *b == b':') {
+                continue;
+            }
+            idx += 1;
+        }
+
+        while idx < bytes.len() && (bytes[idx].is_ascii_whitespace() || bytes[idx] == b'"' || bytes[idx] == b'\'') {
+            idx += 1;
+        }
+
+        let value_start = idx;
+        while idx < bytes.len() && !bytes[idx].is_ascii_whitespace() && !b"\"',;&})]".contains(&bytes[idx]) {
+            idx += 1;
+        }
+
+        if idx > value_start {
+            redacted.push_str(&message[copied..value_start]);
+            redacted.push_str(REDACTED);
+            copied = idx;
+        }
+        pos = idx;
+    }
+
+    redacted.push_str(&message[copied..]);
+    redacted
+}
+
+// Position of the first secret key or scheme at or after the given position
+fn next_secret_key(lower: &str, pos: usize) -> Option<(usize, &'static str, bool)> {
+    let keys = SECRET_KEYS.iter().map(|k| (k, false));
+    let schemes = SECRET_SCHEMES.iter().map(|s| (s, true));
+
+    keys.chain(schemes)
+        .filter_map(|(key, is_scheme)| lower[pos..].find(key).map(|idx| (pos + idx, *key, is_scheme)))
+        .min_by_key(|(idx, _, _)| *idx)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn redactor_redacts_secrets_of_the_config() {
+        let config = Config::parse_from(["kafka2i", "--sasl-password", "hunter2", "--oauth-client-secret", "s3cr3t"]);
+        let redactor = Redactor::new(&config);
+
+        assert_eq!(redact("user alice with hunter2, client s3cr3t", &redactor.secrets), "user alice with ***, client ***");
+    }
+
+    #[test]
+    fn redactor_skips_empty_secrets() {
+        let config = Config::parse_from(["kafka2i", "--sasl-password", ""]);
+        assert!(Redactor::new(&config).secrets.is_empty());
+    }
+
+    #[test]
+    fn redact_secrets_wherever_they_appear() {
+        let secrets = vec!["s3cr3t".to_string()];
+        assert_eq!(redact("user s3cr3t logged s3cr3t", &secrets), "user *** logged ***");
+        assert_eq!(redact("no secrets here", &secrets), "no secrets here");
+    }
+
+    #[test]
+    fn redact_values_of_secret_keys() {
+        assert_eq!(redact_values("connecting with sasl.password=hunter2 to broker"), "connecting with sasl.password=*** to broker");
+        assert_eq!(redact_values("SASL.PASSWORD = hunter2;next"), "SASL.PASSWORD = ***;next");
+        assert_eq!(redact_values("ssl.key.password=p@ss, ssl.keystore.password=ks"), "ssl.key.password=***, ssl.keystore.password=***");
+        assert_eq!(redact_values("client_secret='value' api_key:KEY123"), "client_secret='***' api_key:***");
+        assert_eq!(redact_values("token=abc&access_token=xyz&refresh_token=def"), "token=abc&access_token=***&refresh_token=***");
+    }
+
+    #[test]
+    fn redact_values_of_json() {
+        assert_eq!(
+            redact_values(r#"config: {"sasl.password": "hunter2", "sasl.username": "alice"}"#),
+            r#"config: {"sasl.password": "***", "sasl.username": "alice"}"#
+        );
+    }
+
+    #[test]
+    fn redact_bearer_credentials() {
+        assert_eq!(redact_values("Authorization: Bearer eyJhbGciOi.abc"), "Authorization: Bearer ***");
+        // mechanism names are not schemes
+        assert_eq!(redact_values("using OAUTHBEARER mechanism"), "using OAUTHBEARER mechanism");
+    }
 
-    _logger
-}
\ No newline at end of file
+    #[test]
+    fn redact_values_of_malformed_input() {
+        assert_eq!(redact_values("password"), "password");
+        assert_eq!(redact_values("password="), "password=");
+        assert_eq!(redact_values("bearer"), "bearer");
+        assert_eq!(redact_values(""), "");
+    }
+}
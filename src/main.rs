@@ -2,41 +2,84 @@ use std::{error::Error, io::Stderr, sync::Arc, thread, time::Duration};
 
 use clap::Parser;
 use crossbeam::channel::{bounded, unbounded};
-use crossterm::event::{KeyEventKind, KeyCode};
+use crossterm::event::KeyEventKind;
 use kafka::consumer::StatsContext;
 use parking_lot::Mutex;
 use rdkafka::{consumer::ConsumerContext, ClientConfig, ClientContext, Statistics};
-use crossterm::{terminal::{enable_raw_mode, EnterAlternateScreen, disable_raw_mode, LeaveAlternateScreen}, execute, event::DisableMouseCapture};
+use crossterm::{terminal::{enable_raw_mode, EnterAlternateScreen, disable_raw_mode, LeaveAlternateScreen}, execute, event::{DisableMouseCapture, EnableMouseCapture}};
 use ratatui::{prelude::CrosstermBackend, Terminal};
 use tokio::time;
-use tui::{app::App, app::AppEvent, events};
+use tui::{app::{App, AppEvent, AppOptions}, events, external::ExternalFile, keymap::Keymap, layout_settings::LayoutSettings, saved_state::SavedState, theme::Theme};
 
-use crate::{config::LogLevel, kafka::consumer::{Consumer, DefaultContext}};
+use crate::{config::LogLevel, kafka::{activity::ActivityFetcher, consumer::{Consumer, DefaultContext}, export::Exporter, producer::Producer}};
 use crate::config::Config;
+use crate::certs::ConnectionInfo;
+use crate::logger::LogBuffer;
 use crate::tui::events::TuiEvent;
 
 mod kafka;
+mod certs;
+mod cli;
 mod config;
 mod tui;
 mod logger;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    let logger = logger::initiate();
-    
     // Parsing config from command line args
-    let config = Config::parse();
+    let mut config = Config::parse();
+
+    // cluster of the previous session is connected to if no bootstrap servers are given
+    let saved_state = SavedState::load();
+    if config.bootstrap_servers.is_empty() {
+        config.bootstrap_servers = saved_state.cluster.clone();
+    }
+
     let config_clone = config.clone();
-    logger.parse_new_spec(&config.log_level.to_string())?;
+    let read_only = config.read_only;
+
+    // logger is kept alive till the app exits
+    let (_logger, log_buffer) = logger::initiate(&config)?;
+
+    // inspect SSL certificates, fails early if the client key does not match the certificate
+    let connection_info = certs::inspect(&config)?;
+
+    // subcommands print their output & exit with its code instead of starting the TUI
+    if let Some(command) = config.command.clone() {
+        let client_config: ClientConfig = config.clone().try_into()?;
+        let code = cli::run(command, config.output, config.read_only, &client_config, DefaultContext::new(config_clone));
+        drop(_logger);
+        std::process::exit(code);
+    }
+
+    // load keymap, fails early if the keymap file is invalid
+    let keymap = Keymap::load(config.keymap.as_deref(), config.keymap_preset)?;
+
+    // load theme, fails early if the theme file is invalid
+    let theme = Theme::load(config.theme.as_deref(), config.theme_preset)?;
+
+    // background fetching of the activity can be disabled on large clusters
+    let activity_refresh = (!config.no_activity).then(|| Duration::from_secs(config.activity_refresh_in_secs));
 
     // generate client config
-    let client_config: ClientConfig = config.try_into()?;
+    let client_config: ClientConfig = config.clone().try_into()?;
 
     let default_context = DefaultContext::new(config_clone);
+    let activity_context = default_context.clone();
+
+    // layout & position persisted in the previous session, or the position given as args & the clipboard behaviour
+    // messages are exported with a consumer of their own
+    let options = AppOptions {
+        layout_settings: LayoutSettings::load(),
+        start_position: saved_state.start_position(&config)?,
+        auto_copy: config.auto_copy,
+        exporter: Exporter::new(&client_config, default_context.clone()),
+        producer: if read_only { None } else { Some(Producer::new(&client_config, default_context.clone())?) },
+    };
 
     // Setup Kafka consumer to consume messages
     log::debug!("creating new kafka consumer to consume messages");
-    let message_consumer = Arc::new(Mutex::new(Consumer::new(&client_config, default_context).unwrap()));
+    let message_consumer = Arc::new(Mutex::new(Consumer::new(&client_config, default_context, read_only).unwrap()));
 
     log::debug!("fetching metadata for the first time");
     let metadata = message_consumer.lock().fetch_metadata()?;
@@ -87,12 +130,26 @@ async fn main() -> Result<(), Box<dyn Error>> {
         }
     });
 
+    // size & activity of the topics and lag of the groups are fetched with consumers of their own
+    // fetching a large number of watermarks blocks, so it runs on a thread of its own
+    if let Some(activity_refresh) = activity_refresh {
+        let mut fetcher = ActivityFetcher::new(&client_config, activity_context)?;
+        let activity_consumer = message_consumer.clone();
+        thread::spawn(move || loop {
+            let metadata = activity_consumer.lock().metadata().clone();
+            let activity = fetcher.fetch(&metadata);
+            activity_consumer.lock().update_activity(activity);
+
+            thread::sleep(activity_refresh);
+        });
+    }
+
     //setup TUI
     setup()?;
     
     // Run TUI
     let mut t = Terminal::new(CrosstermBackend::new(std::io::stderr())).unwrap();
-    let result = run(&mut t, message_consumer).await;
+    let result = run(&mut t, message_consumer, connection_info, keymap, theme, options, log_buffer).await;
 
     // Shutdown TUI
     shutdown()?;    
@@ -107,22 +164,38 @@ async fn main() -> Result<(), Box<dyn Error>> {
 fn setup() -> Result<(), Box<dyn Error>>{
     log::debug!("setting up TUI");
     enable_raw_mode()?;
-    execute!(std::io::stderr(), EnterAlternateScreen, DisableMouseCapture)?;
+    execute!(std::io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
     Ok(())
 }
 
 fn shutdown() -> Result<(), Box<dyn Error>> {
     log::debug!("shutting down TUI");
-  execute!(std::io::stderr(), LeaveAlternateScreen)?;
+  execute!(std::io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
   disable_raw_mode()?;
   Ok(())
 }
 
-async fn run<'a, T: ClientContext + ConsumerContext>(t: &'a mut Terminal<CrosstermBackend<Stderr>>, consumer: Arc<Mutex<Consumer<T>>>) -> Result<(), Box<dyn Error>> {
+// Suspend the TUI while the file is opened in the external program, the terminal is restored the same way as on shutdown
+fn open_external(t: &mut Terminal<CrosstermBackend<Stderr>>, events: &mut events::EventHandler, file: ExternalFile) -> Result<(), String> {
+    events.pause();
+    let result = match shutdown() {
+        Ok(()) => file.open(),
+        Err(err) => Err(format!("error while suspending TUI: {}", err)),
+    };
+
+    // screen is cleared so that the whole TUI is redrawn
+    let resumed = setup().and_then(|_| Ok(t.clear()?));
+    events.resume();
+
+    result.and(resumed.map_err(|err| format!("error while resuming TUI: {}", err)))
+}
+
+async fn run<'a, T: ClientContext + ConsumerContext>(t: &'a mut Terminal<CrosstermBackend<Stderr>>, consumer: Arc<Mutex<Consumer<T>>>, connection_info: ConnectionInfo, keymap: Keymap, theme: Theme, options: AppOptions, log_buffer: LogBuffer) -> Result<(), Box<dyn Error>> {
     // ratatui terminal
     let (sender, receiver) = unbounded::<AppEvent>();
-    let mut app = App::new(consumer, receiver).await;
+    let mut app = App::new(consumer, receiver, connection_info, keymap, theme, options, log_buffer).await;
     let app_layout = app.layout();
+    let external_files = app.external_files();
     let mut events = events::EventHandler::new(1.0, 30.0);
 
     let should_quit = Arc::new(Mutex::new(false));
@@ -133,35 +206,29 @@ async fn run<'a, T: ClientContext + ConsumerContext>(t: &'a mut Terminal<Crosste
     thread::scope(|s| {
         s.spawn(|| {
             loop {
+                // app thread sets should_quit once it stops handling events
+                if *should_quit.lock() {
+                    break;
+                }
+
+                // program opened by the app takes over the terminal until it exits
+                if let Ok(file) = external_files.try_recv() {
+                    let result = open_external(t, &mut events, file);
+                    let _ = sender.send(AppEvent::ExternalExited(result));
+                    continue;
+                }
+
                 let event = events.next().unwrap();
                 match event {
-                    TuiEvent::Key(key) => {
-                        match key.kind {
-                            KeyEventKind::Press => {
-                                let _ = match key.code {
-                                    KeyCode::Tab => sender.send(AppEvent::Tab),
-                                    KeyCode::BackTab => sender.send(AppEvent::BackTab),
-                                    KeyCode::Up => sender.send(AppEvent::Up),
-                                    KeyCode::Down => sender.send(AppEvent::Down),
-                                    KeyCode::Left => sender.send(AppEvent::Left),
-                                    KeyCode::Right => sender.send(AppEvent::Right),
-                                    KeyCode::Esc => {
-                                        let res = sender.send(AppEvent::Esc);
-                                        if *should_quit.lock() {
-                                            break;
-                                        }
-                                        res
-                                    },
-                                    KeyCode::Enter => sender.send(AppEvent::Enter),
-                                    KeyCode::Char(':') => sender.send(AppEvent::Edit),
-                                    KeyCode::Char(input) => sender.send(AppEvent::Input(input)),
-                                    KeyCode::Backspace => sender.send(AppEvent::Backspace),
-                                    _ => Ok(())
-                                };
-                            }
-                            // for any other KeyEventKind
-                            _ => ()
-                        }
+                    // keys are resolved by the app based on the keymap & the edit mode
+                    TuiEvent::Key(key) if key.kind == KeyEventKind::Press => {
+                        let _ = sender.send(AppEvent::Key(key));
+                    },
+                    TuiEvent::Mouse(mouse) => {
+                        let _ = sender.send(AppEvent::Mouse(mouse));
+                    },
+                    TuiEvent::Tick => {
+                        let _ = sender.send(AppEvent::Tick);
                     },
                     TuiEvent::Render => {
                        let _ =  t.draw(|f| {
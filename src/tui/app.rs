@@ -1,78 +1,115 @@
 use std::collections::HashMap;
 use std::str::FromStr;
-use std::{char, sync::Arc, time::Duration};
-use crossbeam::channel::Receiver;
-use chrono::{DateTime};
+use std::{char, sync::Arc, thread, time::Duration};
+use crossbeam::channel::{unbounded, Receiver, Sender};
 use log::{debug, error, info};
 use parking_lot::Mutex;
 use rdkafka::message;
 use rdkafka::{consumer::ConsumerContext, ClientContext};
-use strum::{self, Display, EnumString};
-use crate::kafka::consumer::{Consumer, ConsumerError, KafkaMessage};
-use crate::tui::widgets::Direction;
-
-use super::{single_layout::{AppLayout, BROKERS_LIST, CONSUMER_GROUPS_LIST, PARTITIONS_LIST, TOPICS_LIST}, widgets::InputEvent};
-
-// Mode of App
-#[derive(Clone, Debug, Display, Default)]
-enum Mode {
-    #[default]
-    #[strum(to_string="Consumer")]
-    Consumer,
-    #[strum(to_string="Producer")]
-    Producer
-}
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
+use crate::certs::ConnectionInfo;
+use crate::logger::LogBuffer;
+use crate::kafka::consumer::{Consumer, ConsumerError, DefaultContext, KafkaMessage, OffsetBound};
+use crate::kafka::export::{Export, ExportFormat, ExportRange, ExportStatus, Exporter};
+use crate::kafka::producer::{await_delivery, Producer};
+use crate::tui::widgets::{AppWidget, Direction};
+
+use super::bookmarks::{Bookmark, Bookmarks};
+use super::clipboard::{Clipboard, CopyContent};
+use super::command::{self, Command, CommandHistory};
+use super::external::{ExternalFile, Program};
+use super::history::{Location, NavigationHistory};
+use super::keymap::{Action, Keymap};
+use super::notification::{self, Level, Notification};
+use super::layout_settings::LayoutSettings;
+use super::saved_state::SavedState;
+use super::theme::Theme;
+use super::views::View;
+use super::{single_layout::{AppLayout, Zoom, BROKERS_LIST, CONSUMER_GROUPS_LIST, PARTITIONS_LIST, TOPICS_LIST}, widgets::InputEvent};
 
 #[derive(PartialEq)]
 enum EditMode {
     Normal,
-    Editing
+    Editing,
+    Filtering,
+    // editing the form of the producer view
+    Producing,
 }
 
 pub enum AppEvent {
-    Tab,
-    BackTab,
-    Up,
-    Down,
-    Left,
-    Right,
-    Esc,
-    Edit,
-    Input(char),
-    Backspace,
-    Enter,
-}
-
-// AppCMDs
-#[derive(Clone, Debug, Display, PartialEq, EnumString)]
-enum Command {
-    #[strum(serialize = ":offset")]
-    Offset,
-    #[strum(serialize = ":ts")]
-    Timestamp,
-    Invalid,
-}
-
-// AppErr
-const ERR_INVALID_CMD: &str = "err:InvalidCMD";
-const ERR_INVALID_OFFSET: &str = "err:InvalidOffset";
-const ERR_INVALID_TIMESTAMP: &str = "err:InvalidTimestamp";
-const ERR_NO_SELECTED_PARTITION: &str = "err:NoSelectedPartition";
-const ERR_FETCHING_OFFSET: &str = "err:FetchingOffset";
-const ERR_OFFSET_NOT_FOUND: &str = "err:OffsetNotFound";
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    Tick,
+    // external program opened by the app exited & the TUI is resumed
+    ExternalExited(Result<(), String>),
+}
+
+enum FilterEvent {
+    NewChar(char),
+    RemovePrevChar,
+    Clear,
+    Submit,
+}
+
+// Options the app is started with
+pub struct AppOptions {
+    pub layout_settings: LayoutSettings,
+    // position to open the app at, restored from the previous session or given as args
+    pub start_position: Option<SavedState>,
+    // copy every fetched message to the clipboard
+    pub auto_copy: bool,
+    pub exporter: Exporter,
+    // producer of the producer view, none in read-only mode
+    pub producer: Option<Producer<DefaultContext>>,
+}
 
 const UNINITIALISED_OFFSET: i64 = -999;
+const MAX_SESSIONS: usize = 4;
+
+// Topic & the partition & offset the message was delivered at, or the error
+type DeliveryResult = (String, Result<(i32, i64), String>);
+
+// Partition browsing session, each session keeps its own partition, offset, message & navigation history
+#[derive(Clone)]
+struct Session {
+    // partition in the format topic/partition
+    partition: Option<String>,
+    offset: i64,
+    message: Option<KafkaMessage>,
+    history: NavigationHistory,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session {
+            partition: None,
+            offset: UNINITIALISED_OFFSET,
+            message: None,
+            history: NavigationHistory::default(),
+        }
+    }
+}
 
 // App state maintains the state at app level
 struct AppState {
     // should_quit tells the main loop to terminate the app
     should_quit: bool,
-    //mode
-    mode: Mode,
     //edit mode
     edit_mode: EditMode,
-    //offset for selected partition
-    offset: i64,
+    //partition sessions, messages are fetched in the active session
+    sessions: Vec<Session>,
+    active_session: usize,
+    //read-only mode
+    read_only: bool,
+    //copy every fetched message to the clipboard
+    auto_copy: bool,
+    //scrollbar of the message pane is being dragged
+    dragging_scrollbar: bool,
+    //position to open the app at, taken once the events are handled
+    start_position: Option<SavedState>,
+    //export running in the background
+    export: Option<Export>,
 }
 
 // App is the high level struct containing
@@ -86,44 +123,82 @@ where T: ClientContext + ConsumerContext
     state: AppState,
     kafka_consumer: Arc<Mutex<Consumer<T>>>,
     app_event_recv: Receiver<AppEvent>,
-    clipboard: Option<arboard::Clipboard>,
+    // files to be opened in external programs, the terminal is handed over to the program by the render loop
+    external_send: Sender<ExternalFile>,
+    external_recv: Receiver<ExternalFile>,
+    clipboard: Clipboard,
+    bookmarks: Bookmarks,
+    exporter: Exporter,
+    producer: Option<Producer<DefaultContext>>,
+    // deliveries of the messages produced in the producer view, awaited in the background
+    delivery_send: Sender<DeliveryResult>,
+    delivery_recv: Receiver<DeliveryResult>,
+    connection_info: ConnectionInfo,
+    keymap: Keymap,
+    command_history: CommandHistory,
 }
 
 // This impl block only defines the new state of the app
 impl <'a, T> App<'a, T> 
 where T: ClientContext + ConsumerContext
 {
-    pub async fn new(kafka_consumer: Arc<Mutex<Consumer<T>>>, app_event_recv: Receiver<AppEvent>) -> App<'a, T> {
+    pub async fn new(kafka_consumer: Arc<Mutex<Consumer<T>>>, app_event_recv: Receiver<AppEvent>, connection_info: ConnectionInfo, keymap: Keymap, theme: Theme, options: AppOptions, log_buffer: LogBuffer) -> App<'a, T> {
         let metadata = kafka_consumer.lock().metadata().clone();
-        let mode = Mode::default();
+        let read_only = kafka_consumer.lock().read_only();
+        let expiry_warnings = connection_info.expiry_warnings();
+        let (external_send, external_recv) = unbounded::<ExternalFile>();
+        let (delivery_send, delivery_recv) = unbounded::<DeliveryResult>();
 
         let app = App {
-            layout: Arc::new(Mutex::new(AppLayout::new(&metadata))),
+            layout: Arc::new(Mutex::new(AppLayout::new(&metadata, &connection_info, &keymap, theme, options.layout_settings, log_buffer, read_only))),
             state: AppState {
                 should_quit: false,
-                mode: mode.clone(),
                 edit_mode: EditMode::Normal,
-                offset: UNINITIALISED_OFFSET,
+                sessions: vec![Session::default()],
+                active_session: 0,
+                read_only,
+                auto_copy: options.auto_copy,
+                dragging_scrollbar: false,
+                start_position: options.start_position,
+                export: None,
             },
             //terminal: t,
             kafka_consumer,
             app_event_recv,
-            clipboard: match arboard::Clipboard::new() {
-                Ok(c) => Some(c),
-                Err(err) => {
-                    error!("error initiating clipboard {}", err);
-                    None
-                } 
-            },
+            external_send,
+            external_recv,
+            clipboard: Clipboard::new(),
+            bookmarks: Bookmarks::load(),
+            exporter: options.exporter,
+            producer: options.producer,
+            delivery_send,
+            delivery_recv,
+            connection_info,
+            keymap,
+            command_history: CommandHistory::load(),
         };
 
-        app.layout.lock().footer_layout.update_mode(mode.to_string());
+        app.layout.lock().footer_layout.update_mode(View::default().to_string(), read_only);
+        app.refresh_bookmarks();
+
+        // warn about certificates which are about to expire
+        if !expiry_warnings.is_empty() {
+            for warning in &expiry_warnings {
+                app.notify(Level::Warn, warning.clone());
+            }
+            app.layout.lock().footer_layout.set_warning(expiry_warnings.join(" | "));
+        }
+
         app
     }
 
     pub fn layout(&self) -> Arc<Mutex<AppLayout<'a>>> {
         self.layout.clone()
     }
+
+    pub fn external_files(&self) -> Receiver<ExternalFile> {
+        self.external_recv.clone()
+    }
 }
 
 // This impl block for the app event handler
@@ -136,46 +211,156 @@ where T: ClientContext + ConsumerContext {
     
     // Event handler which defines the high level handlers for every type of event handled in TUI
     pub fn event_handler(&mut self) {
+        // the position is opened while the TUI is rendered, so that the progress of fetching the message is shown
+        if let Some(position) = self.state.start_position.take() {
+            self.open_position(position);
+        }
+
         loop {
             match self.app_event_recv.recv() {
-                Ok(event) => {
+                Ok(AppEvent::Tick) => {
+                    self.handle_metadata_refresh();
+                    self.refresh_export_progress();
+                    self.refresh_deliveries();
+                    let mut layout = self.layout.lock();
+                    layout.footer_layout.expire_notification();
+                    if layout.show_logs {
+                        layout.logs_layout.refresh_if_following();
+                    }
+                },
+                Ok(AppEvent::ExternalExited(result)) => {
+                    if let Err(err) = result {
+                        self.notify(Level::Error, err);
+                    }
+                },
+                Ok(AppEvent::Mouse(mouse)) => {
+                    // mouse is ignored while editing the command or the producer form
+                    if !matches!(self.state.edit_mode, EditMode::Editing | EditMode::Producing) {
+                        self.handle_mouse_event(mouse);
+                    }
+                },
+                Ok(AppEvent::Key(key)) => {
                     match self.state.edit_mode {
+                        // keys in normal mode are resolved to actions with the keymap
                         EditMode::Normal => {
-                            match event {
-                                AppEvent::Tab => self.handle_tab(false),
-                                AppEvent::BackTab => self.handle_tab(true),
-                                AppEvent::Up => self.handle_list_navigation(Direction::UP),
-                                AppEvent::Down => self.handle_list_navigation(Direction::DOWN),
-                                AppEvent::Left => self.handle_offset_navigation(Direction::LEFT),
-                                AppEvent::Right => self.handle_offset_navigation(Direction::RIGHT),
-                                AppEvent::Esc => {
+                            let action = self.keymap.action(&key);
+
+                            // bookmarks & navigation history are navigated & opened while they are shown
+                            if self.handle_bookmarks_action(action) || self.handle_history_action(action) {
+                                continue;
+                            }
+
+                            // notification history & log pane are scrolled while they are shown
+                            if self.handle_pane_scroll(action) {
+                                continue;
+                            }
+
+                            // views other than the consumer view handle the navigation themselves
+                            if self.handle_view_action(action) {
+                                continue;
+                            }
+
+                            match action {
+                                Some(Action::NextList) => self.handle_tab(false),
+                                Some(Action::PrevList) => self.handle_tab(true),
+                                Some(Action::Up) => self.handle_list_navigation(Direction::UP),
+                                Some(Action::Down) => self.handle_list_navigation(Direction::DOWN),
+                                Some(Action::PrevOffset) => self.handle_offset_navigation(Direction::LEFT),
+                                Some(Action::NextOffset) => self.handle_offset_navigation(Direction::RIGHT),
+                                Some(Action::Back) => self.handle_history_navigation(true),
+                                Some(Action::Forward) => self.handle_history_navigation(false),
+                                Some(Action::History) => self.handle_history_command(),
+                                Some(Action::Quit) => {
+                                    self.save_state();
                                     self.state.should_quit = true;
                                     break;
                                 },
-                                AppEvent::Edit => self.toggle_edit_mode(EditMode::Editing),
-                                AppEvent::Input(char) => match char {
-                                    'm' | 'M' => self.handle_message_scroll(Direction::DOWN),
-                                    'n' | 'N' => self.handle_message_scroll(Direction::UP),
-                                    'h' => self.handle_help_command(),
-                                    _ => (),
+                                Some(Action::ZoomList) => self.layout.lock().main_layout.toggle_zoom(Zoom::List),
+                                Some(Action::ZoomMessage) => self.layout.lock().main_layout.toggle_zoom(Zoom::Message),
+                                Some(Action::GrowLists) => self.update_layout_settings(LayoutSettings::grow_lists),
+                                Some(Action::ShrinkLists) => self.update_layout_settings(LayoutSettings::shrink_lists),
+                                Some(Action::ToggleLayout) => self.update_layout_settings(LayoutSettings::toggle_orientation),
+                                Some(Action::ToggleInternalTopics) => self.update_topic_settings(LayoutSettings::toggle_internal_topics),
+                                Some(Action::ToggleStreamTopics) => self.update_topic_settings(LayoutSettings::toggle_stream_topics),
+                                Some(Action::GroupTopics) => self.update_topic_settings(LayoutSettings::toggle_topic_groups),
+                                Some(Action::Sort) => self.handle_sort(),
+                                Some(Action::Select) => {
+                                    self.layout.lock().main_layout.lists_layout.selected_list_mut().toggle_group();
                                 },
+                                Some(Action::Edit) => self.toggle_edit_mode(EditMode::Editing),
+                                Some(Action::ScrollMessageDown) => self.handle_message_scroll(Direction::DOWN),
+                                Some(Action::ScrollMessageUp) => self.handle_message_scroll(Direction::UP),
+                                Some(Action::Help) => self.handle_help_command(),
+                                Some(Action::ConnectionInfo) => self.handle_connection_info_command(),
+                                Some(Action::Notifications) => self.handle_notifications_command(),
+                                Some(Action::Logs) => self.handle_logs_command(),
+                                Some(Action::LogLevel) => self.layout.lock().logs_layout.cycle_level(),
+                                Some(Action::FollowLogs) => self.layout.lock().logs_layout.toggle_follow(),
+                                Some(Action::Filter) => self.toggle_edit_mode(EditMode::Filtering),
+                                Some(Action::CopyMessage) => self.handle_copy(CopyContent::Message),
+                                Some(Action::CopyKey) => self.handle_copy(CopyContent::Key),
+                                Some(Action::CopyPayload) => self.handle_copy(CopyContent::Payload),
+                                Some(Action::CopyHeaders) => self.handle_copy(CopyContent::Headers),
+                                Some(Action::CopyJson) => self.handle_copy(CopyContent::Json),
+                                Some(Action::CopyKcat) => self.handle_copy(CopyContent::Kcat),
+                                Some(Action::OpenEditor) => self.handle_open(Program::Editor, false),
+                                Some(Action::OpenPager) => self.handle_open(Program::Pager, false),
+                                Some(Action::OpenPayload) => self.handle_open(Program::Pager, true),
+                                Some(Action::Bookmark) => self.handle_bookmark_command(""),
+                                Some(Action::Bookmarks) => self.handle_bookmarks_command(),
+                                Some(Action::DeleteBookmark) => (),
+                                Some(Action::NewSession) => self.handle_new_session(),
+                                Some(Action::CloseSession) => self.handle_close_session(),
+                                Some(Action::NextSession) => self.handle_next_session(),
+                                Some(Action::NextView) => self.handle_view_switch(false),
+                                Some(Action::PrevView) => self.handle_view_switch(true),
+                                None => (),
+                            }
+                        },
+                        EditMode::Filtering => {
+                            match key.code {
+                                KeyCode::Esc => self.handle_filter_event(FilterEvent::Clear),
+                                KeyCode::Char(char) => self.handle_filter_event(FilterEvent::NewChar(char)),
+                                KeyCode::Backspace => self.handle_filter_event(FilterEvent::RemovePrevChar),
+                                KeyCode::Up => self.handle_filter_navigation(Direction::UP),
+                                KeyCode::Down => self.handle_filter_navigation(Direction::DOWN),
+                                KeyCode::Enter => self.handle_filter_event(FilterEvent::Submit),
                                 _ => (),
                             }
                         },
                         EditMode::Editing => {
-                            match event {
-                                AppEvent::Esc => self.toggle_edit_mode(EditMode::Normal),
-                                AppEvent::Input(char) => self.handle_input_event(InputEvent::NewChar(char)),
-                                AppEvent::Backspace => self.handle_input_event(InputEvent::RemovePrevChar),
-                                AppEvent::Left => self.handle_input_event(InputEvent::MoveCursor(Direction::LEFT)),
-                                AppEvent::Right => self.handle_input_event(InputEvent::MoveCursor(Direction::RIGHT)),
-                                AppEvent::Enter => {
+                            match key.code {
+                                KeyCode::Esc => {
+                                    self.layout.lock().footer_layout.set_input_hint(None);
+                                    self.toggle_edit_mode(EditMode::Normal);
+                                },
+                                KeyCode::Char(char) => self.handle_input_event(InputEvent::NewChar(char)),
+                                KeyCode::Tab => self.handle_command_completion(),
+                                KeyCode::Up => self.handle_command_history(Direction::UP),
+                                KeyCode::Down => self.handle_command_history(Direction::DOWN),
+                                KeyCode::Backspace => self.handle_input_event(InputEvent::RemovePrevChar),
+                                KeyCode::Left => self.handle_input_event(InputEvent::MoveCursor(Direction::LEFT)),
+                                KeyCode::Right => self.handle_input_event(InputEvent::MoveCursor(Direction::RIGHT)),
+                                KeyCode::Enter => {
                                     self.handle_input_submission();
                                     self.toggle_edit_mode(EditMode::Normal);
                                 },
                                 _ => (),
                             }
                         },
+                        EditMode::Producing => {
+                            match key.code {
+                                KeyCode::Esc => self.toggle_edit_mode(EditMode::Normal),
+                                KeyCode::Char(char) => self.layout.lock().producer_layout.handle_input_event(InputEvent::NewChar(char)),
+                                KeyCode::Backspace => self.layout.lock().producer_layout.handle_input_event(InputEvent::RemovePrevChar),
+                                KeyCode::Left => self.layout.lock().producer_layout.handle_input_event(InputEvent::MoveCursor(Direction::LEFT)),
+                                KeyCode::Right => self.layout.lock().producer_layout.handle_input_event(InputEvent::MoveCursor(Direction::RIGHT)),
+                                KeyCode::Tab | KeyCode::Down => self.layout.lock().producer_layout.switch_field(false),
+                                KeyCode::BackTab | KeyCode::Up => self.layout.lock().producer_layout.switch_field(true),
+                                KeyCode::Enter => self.handle_produce(),
+                                _ => (),
+                            }
+                        },
                     }
                 },
                 Err(_) => log::error!("error occured while receiving app event")
@@ -197,6 +382,11 @@ where T: ClientContext + ConsumerContext {
     fn handle_list_navigation(&mut self, direction: Direction){
         // selected list
         self.layout.lock().main_layout.lists_layout.handle_navigation(direction);
+        self.handle_list_selection();
+    }
+
+    // Handles the selection of an item in the list in focus
+    fn handle_list_selection(&mut self) {
         let selected_list_name = self.layout.lock().main_layout.lists_layout.selected_list().name().to_string().clone();
 
         // handle navigation events
@@ -253,7 +443,7 @@ where T: ClientContext + ConsumerContext {
     fn handle_partition_list_navigation(&mut self) {
         if let Some(selected_partition) = self.get_selected_item_for_list(PARTITIONS_LIST) {
             // reset the stored offset after selecting a new partition
-            self.state.offset = UNINITIALISED_OFFSET;
+            self.session().offset = UNINITIALISED_OFFSET;
             self.fetch_message(&selected_partition, -1);
         }
     }
@@ -269,6 +459,96 @@ where T: ClientContext + ConsumerContext {
         }
     }
 
+    // Navigates the filtered list in focus or scrolls the searched log pane
+    fn handle_filter_navigation(&mut self, direction: Direction) {
+        let show_logs = self.layout.lock().show_logs;
+        if !show_logs {
+            self.handle_list_navigation(direction);
+            return;
+        }
+
+        match direction {
+            Direction::UP => self.layout.lock().logs_layout.handle_up(),
+            _ => self.layout.lock().logs_layout.handle_down(),
+        }
+    }
+
+    // Handles filter events for the list in focus, or the search of the log pane if it is shown
+    fn handle_filter_event(&mut self, filter_event: FilterEvent) {
+        let show_logs = self.layout.lock().show_logs;
+        if show_logs {
+            self.handle_log_search_event(filter_event);
+            return;
+        }
+
+        let mut layout = self.layout.lock();
+        let list = layout.main_layout.lists_layout.selected_list_mut();
+        let mut filter = list.filter().unwrap_or_default().to_string();
+
+        match filter_event {
+            FilterEvent::NewChar(c) => {
+                filter.push(c);
+                list.set_filter(Some(filter));
+            },
+            FilterEvent::RemovePrevChar => {
+                filter.pop();
+                list.set_filter(Some(filter));
+            },
+            FilterEvent::Clear => {
+                list.set_filter(None);
+                drop(layout);
+                self.toggle_edit_mode(EditMode::Normal);
+            },
+            FilterEvent::Submit => {
+                // keep the filter applied unless it is empty
+                if filter.is_empty() {
+                    list.set_filter(None);
+                }
+                drop(layout);
+                self.toggle_edit_mode(EditMode::Normal);
+                self.handle_list_selection();
+            },
+        }
+    }
+
+    // Handles search events of the log pane
+    fn handle_log_search_event(&mut self, filter_event: FilterEvent) {
+        let mut layout = self.layout.lock();
+        let mut search = layout.logs_layout.search().unwrap_or_default().to_string();
+
+        match filter_event {
+            FilterEvent::NewChar(c) => {
+                search.push(c);
+                layout.logs_layout.set_search(Some(search));
+            },
+            FilterEvent::RemovePrevChar => {
+                search.pop();
+                layout.logs_layout.set_search(Some(search));
+            },
+            FilterEvent::Clear | FilterEvent::Submit => {
+                // keep the search applied unless it is empty or cleared
+                if search.is_empty() || matches!(filter_event, FilterEvent::Clear) {
+                    layout.logs_layout.set_search(None);
+                }
+                drop(layout);
+                self.toggle_edit_mode(EditMode::Normal);
+            },
+        }
+    }
+
+    // Refreshes lists with the latest metadata & activity
+    // skipped if the consumer is busy, it will be picked up in the next tick
+    fn handle_metadata_refresh(&mut self) {
+        let (metadata, activity) = match self.kafka_consumer.try_lock() {
+            Some(consumer) => (consumer.metadata().clone(), consumer.activity().clone()),
+            None => return,
+        };
+
+        let mut layout = self.layout.lock();
+        layout.main_layout.refresh_lists(&metadata, &activity);
+        layout.refresh_views(&metadata, &activity);
+    }
+
     // Gets the selected item for the list
     fn get_selected_item_for_list(&mut self, list_name: &str) -> Option<String> {
         if let Some(list) = self.layout.lock().main_layout.lists_layout.get_list_by_name(list_name) {
@@ -284,13 +564,729 @@ impl <T> App<'_, T>
 where T: ClientContext + ConsumerContext {
     fn handle_message_scroll(&mut self, direction: Direction) {
         match direction {
-            Direction::DOWN => self.layout.lock().main_layout.details_layout.message.handle_down(),
-            Direction::UP => self.layout.lock().main_layout.details_layout.message.handle_up(),
+            Direction::DOWN => self.layout.lock().main_layout.details_layout.message().handle_down(),
+            Direction::UP => self.layout.lock().main_layout.details_layout.message().handle_up(),
             _ => ()
         }
     }
 }
 
+// Implementation block for partition sessions
+impl <T> App<'_, T>
+where T: ClientContext + ConsumerContext {
+    fn session(&mut self) -> &mut Session {
+        &mut self.state.sessions[self.state.active_session]
+    }
+
+    // Opens a new session on the partition & offset of the active session
+    fn handle_new_session(&mut self) {
+        if self.state.sessions.len() >= MAX_SESSIONS {
+            self.notify(Level::Warn, format!("at most {} sessions can be opened", MAX_SESSIONS));
+            return;
+        }
+
+        let session = self.session().clone();
+        self.state.sessions.push(session.clone());
+        self.state.active_session = self.state.sessions.len() - 1;
+        self.layout.lock().main_layout.details_layout.add_session();
+
+        if let Some(message) = &session.message {
+            self.show_message(message);
+        }
+
+        self.notify(Level::Info, format!("opened session {} of {}", self.state.active_session + 1, self.state.sessions.len()));
+    }
+
+    // Closes the active session, the last session is never closed
+    fn handle_close_session(&mut self) {
+        if self.state.sessions.len() == 1 {
+            self.notify(Level::Warn, "the last session cannot be closed".to_string());
+            return;
+        }
+
+        let idx = self.state.active_session;
+        self.state.sessions.remove(idx);
+        self.layout.lock().main_layout.details_layout.remove_session(idx);
+        self.activate_session(idx.min(self.state.sessions.len() - 1));
+        self.notify(Level::Info, format!("closed session {}, {} sessions left", idx + 1, self.state.sessions.len()));
+    }
+
+    fn handle_next_session(&mut self) {
+        let next = (self.state.active_session + 1) % self.state.sessions.len();
+        self.activate_session(next);
+    }
+
+    // Activates the session & selects its topic & partition in the lists
+    // the message is not fetched again, the offset of the session is continued from
+    fn activate_session(&mut self, idx: usize) {
+        self.state.active_session = idx;
+        self.layout.lock().main_layout.details_layout.activate_session(idx);
+
+        self.refresh_history();
+        if let Some(partition) = self.session().partition.clone() {
+            self.select_partition(&partition);
+        }
+    }
+
+    // Select the topic & the partition in the lists without fetching the message
+    fn select_partition(&mut self, partition: &str) {
+        let Some((topic, _)) = get_topic_and_parition_id(partition) else {
+            return;
+        };
+
+        let topic_selected = self.layout.lock().main_layout.lists_layout.get_list_by_name(TOPICS_LIST)
+            .is_some_and(|l| l.select_value(topic));
+        if topic_selected {
+            self.handle_topic_list_navigation();
+            if let Some(list) = self.layout.lock().main_layout.lists_layout.get_list_by_name(PARTITIONS_LIST) {
+                list.select_value(partition);
+            }
+        }
+    }
+}
+
+// Implementation block for the navigation history of the active session
+impl <T> App<'_, T>
+where T: ClientContext + ConsumerContext {
+    // Fetch the previous or the next location in the history
+    fn handle_history_navigation(&mut self, back: bool) {
+        let location = {
+            let history = &mut self.session().history;
+            if back { history.back().cloned() } else { history.forward().cloned() }
+        };
+
+        match location {
+            Some(location) => self.open_location(location),
+            None => self.notify(Level::Warn, format!("no {} location in the history", if back { "previous" } else { "next" })),
+        }
+    }
+
+    fn handle_history_command(&mut self) {
+        self.refresh_history();
+        let mut layout = self.layout.lock();
+        layout.show_history = !layout.show_history;
+    }
+
+    // Navigates & opens the history while it is shown
+    // returns false if the history is not shown or the action is not handled by it
+    fn handle_history_action(&mut self, action: Option<Action>) -> bool {
+        if !self.layout.lock().show_history {
+            return false;
+        }
+
+        match action {
+            Some(Action::Up | Action::ScrollMessageUp) => self.layout.lock().history_layout.handle_navigation(Direction::UP),
+            Some(Action::Down | Action::ScrollMessageDown) => self.layout.lock().history_layout.handle_navigation(Direction::DOWN),
+            Some(Action::Select) => {
+                let selected = self.layout.lock().history_layout.selected();
+                let location = selected.and_then(|idx| self.session().history.go_to(idx).cloned());
+                if let Some(location) = location {
+                    self.layout.lock().show_history = false;
+                    self.select_view(View::Consumer);
+                    self.open_location(location);
+                }
+            },
+            _ => return false,
+        }
+
+        true
+    }
+
+    // Select the partition of the location & fetch the message, the location is not visited again
+    fn open_location(&mut self, location: Location) {
+        self.select_partition(&location.partition);
+        self.fetch_message(&location.partition, location.offset);
+    }
+
+    fn refresh_history(&mut self) {
+        let history = self.session().history.clone();
+        self.layout.lock().history_layout.update(&history);
+    }
+}
+
+// Implementation block for the position persisted between sessions
+impl <T> App<'_, T>
+where T: ClientContext + ConsumerContext {
+    // Select the topic & partition and fetch the message at the offset or the timestamp, the latest message by default
+    fn open_position(&mut self, position: SavedState) {
+        let Some(topic) = position.topic.as_deref() else {
+            return;
+        };
+
+        info!("opening the app at {}/{:?} offset {:?} timestamp {:?}", topic, position.partition, position.offset, position.timestamp);
+        self.handle_topic_command(topic);
+        if self.get_selected_item_for_list(TOPICS_LIST).as_deref() != Some(topic) {
+            return;
+        }
+
+        if let Some(partition_id) = position.partition {
+            // the partition is selected without fetching the latest message, it is fetched once at the given position
+            let partition = format!("{}/{}", topic, partition_id);
+            let selected = self.layout.lock().main_layout.lists_layout.get_list_by_name(PARTITIONS_LIST)
+                .is_some_and(|l| l.select_value(&partition));
+            if !selected {
+                self.notify(Level::Error, format!("partition {} not found", partition));
+                return;
+            }
+
+            self.focus_list(PARTITIONS_LIST);
+            match position.timestamp {
+                Some(timestamp) => self.handle_timestamp_command(timestamp),
+                None => self.fetch_message(&partition, position.offset.unwrap_or(-1)),
+            }
+        }
+
+        if let Some(list) = &position.list {
+            self.focus_list(list);
+        }
+    }
+
+    // Persist the list in focus along with the topic, partition & offset of the active session
+    fn save_state(&mut self) {
+        let (list, selected_topic) = {
+            let mut layout = self.layout.lock();
+            let list = layout.main_layout.lists_layout.selected_list().name().to_string();
+            let topic = layout.main_layout.lists_layout.get_list_by_name(TOPICS_LIST).and_then(|l| l.selected_item());
+            (list, topic)
+        };
+
+        let session = self.session().clone();
+        let (topic, partition, offset) = match session.partition.as_deref().and_then(get_topic_and_parition_id) {
+            Some((topic, partition_id)) => (Some(topic.to_string()), Some(partition_id), (session.offset != UNINITIALISED_OFFSET).then_some(session.offset)),
+            None => (selected_topic, None, None),
+        };
+
+        SavedState {
+            cluster: self.connection_info.bootstrap_servers.clone(),
+            list: Some(list),
+            topic,
+            partition,
+            offset,
+            timestamp: None,
+        }.save();
+    }
+
+    fn focus_list(&mut self, list_name: &str) {
+        let mut layout = self.layout.lock();
+        let lists_layout = &mut layout.main_layout.lists_layout;
+        if let Some(idx) = lists_layout.lists.iter().position(|l| l.name() == list_name) {
+            lists_layout.focus(idx);
+        }
+    }
+}
+
+// Implementation block for bookmarks
+impl <T> App<'_, T>
+where T: ClientContext + ConsumerContext {
+    // Bookmark the message of the active session, the note of an existing bookmark is replaced
+    fn handle_bookmark_command(&mut self, note: &str) {
+        let Some(message) = self.session().message.clone() else {
+            self.notify(Level::Warn, "no message to bookmark, fetch a message first".to_string());
+            return;
+        };
+
+        let bookmark = Bookmark::new(&self.connection_info.bootstrap_servers, &message.topic, message.partition, message.offset, note);
+        let position = bookmark.position();
+        let added = self.bookmarks.add(bookmark);
+        self.refresh_bookmarks();
+
+        if added {
+            self.notify(Level::Info, format!("bookmarked {}", position));
+        } else {
+            self.notify(Level::Info, format!("updated the note of bookmark {}", position));
+        }
+    }
+
+    fn handle_bookmarks_command(&mut self) {
+        let mut layout = self.layout.lock();
+        layout.show_bookmarks = !layout.show_bookmarks;
+    }
+
+    fn handle_export_bookmarks_command(&mut self, file: &str) {
+        match self.bookmarks.export(file) {
+            Ok(count) => self.notify(Level::Info, format!("exported {} bookmarks to {}", count, file)),
+            Err(err) => self.notify(Level::Error, format!("error while exporting bookmarks to {}: {}", file, err)),
+        }
+    }
+
+    // bookmarks of the other clusters are imported as well, they are listed when connected to the cluster
+    fn handle_import_bookmarks_command(&mut self, file: &str) {
+        match self.bookmarks.import(file) {
+            Ok(count) => {
+                self.refresh_bookmarks();
+                self.notify(Level::Info, format!("imported {} bookmarks from {}", count, file));
+            },
+            Err(err) => self.notify(Level::Error, format!("error while importing bookmarks from {}: {}", file, err)),
+        }
+    }
+
+    // Navigates, opens or deletes the bookmarks while they are shown
+    // returns false if the bookmarks are not shown or the action is not handled by them
+    fn handle_bookmarks_action(&mut self, action: Option<Action>) -> bool {
+        if !self.layout.lock().show_bookmarks {
+            return false;
+        }
+
+        match action {
+            Some(Action::Up | Action::ScrollMessageUp) => self.layout.lock().bookmarks_layout.handle_navigation(Direction::UP),
+            Some(Action::Down | Action::ScrollMessageDown) => self.layout.lock().bookmarks_layout.handle_navigation(Direction::DOWN),
+            Some(Action::Select) => self.open_bookmark(),
+            Some(Action::DeleteBookmark) => {
+                let selected = self.layout.lock().bookmarks_layout.selected().cloned();
+                if let Some(bookmark) = selected {
+                    self.bookmarks.remove(&bookmark);
+                    self.refresh_bookmarks();
+                    self.notify(Level::Info, format!("deleted bookmark {}", bookmark.position()));
+                }
+            },
+            _ => return false,
+        }
+
+        true
+    }
+
+    // Fetch the bookmarked message in the active session, the bookmarks are hidden
+    fn open_bookmark(&mut self) {
+        let selected = self.layout.lock().bookmarks_layout.selected().cloned();
+        let Some(bookmark) = selected else {
+            return;
+        };
+
+        self.layout.lock().show_bookmarks = false;
+        self.select_view(View::Consumer);
+        self.open_position(SavedState {
+            cluster: bookmark.cluster,
+            list: None,
+            topic: Some(bookmark.topic),
+            partition: Some(bookmark.partition),
+            offset: Some(bookmark.offset),
+            timestamp: None,
+        });
+    }
+
+    fn refresh_bookmarks(&self) {
+        let bookmarks = self.bookmarks.of_cluster(&self.connection_info.bootstrap_servers);
+        self.layout.lock().bookmarks_layout.update(bookmarks);
+    }
+}
+
+// Implementation block for exports
+impl <T> App<'_, T>
+where T: ClientContext + ConsumerContext {
+    // Export the range of the selected partition in the background, one export runs at a time
+    fn handle_export_command(&mut self, from: OffsetBound, to: OffsetBound, file: &str, format: ExportFormat) {
+        if let Some(export) = &self.state.export {
+            self.notify(Level::Warn, format!("export to {} is still running, cancel it with :cancel-export", export.path));
+            return;
+        }
+
+        let Some(selected_partition) = self.get_selected_item_for_list(PARTITIONS_LIST) else {
+            self.notify(Level::Error, "no partition selected to export, select a partition first".to_string());
+            return;
+        };
+
+        let Some((topic, partition)) = get_topic_and_parition_id(&selected_partition) else {
+            self.notify(Level::Error, format!("invalid partition {}", selected_partition));
+            return;
+        };
+
+        let range = ExportRange { topic: topic.to_string(), partition, from, to };
+        self.state.export = Some(self.exporter.start(range, file, format));
+        self.notify(Level::Info, format!("exporting {} as {} to {}", selected_partition, format, file));
+        self.refresh_export_progress();
+    }
+
+    // the export stops at the next message, the messages exported until then are kept
+    fn handle_cancel_export_command(&mut self) {
+        match &self.state.export {
+            Some(export) => {
+                export.cancel();
+                self.notify(Level::Info, format!("cancelling export to {} ...", export.path));
+            },
+            None => self.notify(Level::Warn, "no export is running".to_string()),
+        }
+    }
+
+    // Show the progress of the running export in the footer, the outcome is notified once it finishes
+    fn refresh_export_progress(&mut self) {
+        let Some(export) = &self.state.export else {
+            return;
+        };
+
+        let progress = export.progress();
+        let partition = format!("{}/{}", export.range.topic, export.range.partition);
+        let (path, format, exported) = (export.path.clone(), export.format, progress.exported);
+
+        match progress.status {
+            ExportStatus::Running => {
+                let total = match progress.total {
+                    Some(total) if total > 0 => format!("{}/{} messages ({}%)", exported, total, exported * 100 / total),
+                    _ => format!("{} messages", exported),
+                };
+                self.layout.lock().footer_layout.set_progress(Some(format!("exporting {}: {}, :cancel-export to cancel", partition, total)));
+                return;
+            },
+            ExportStatus::Completed => self.notify(Level::Info, format!("exported {} messages of {} as {} to {}", exported, partition, format, path)),
+            ExportStatus::Cancelled => self.notify(Level::Warn, format!("export of {} cancelled, {} messages were exported to {}", partition, exported, path)),
+            ExportStatus::Failed(err) => self.notify(Level::Error, format!("export of {} to {} failed after {} messages: {}", partition, path, exported, err)),
+        }
+
+        self.state.export = None;
+        self.layout.lock().footer_layout.set_progress(None);
+    }
+}
+
+// Implementation block for the producer view
+impl <T> App<'_, T>
+where T: ClientContext + ConsumerContext {
+    // Send the message of the producer form, the delivery is awaited in the background & shown once it completes
+    fn handle_produce(&mut self) {
+        let Some(producer) = &self.producer else {
+            self.notify(Level::Warn, "producing messages is disabled in read-only mode".to_string());
+            return;
+        };
+
+        let record = self.layout.lock().producer_layout.record();
+        let (topic, record) = match record {
+            Ok(record) => record,
+            Err(err) => {
+                self.notify(Level::Error, err);
+                return;
+            },
+        };
+
+        // messages to unknown topics would only fail once the delivery times out
+        if self.kafka_consumer.lock().metadata().get_topic(&topic).is_none() {
+            self.notify(Level::Error, format!("topic {} not found", topic));
+            return;
+        }
+
+        match producer.send(&topic, &record) {
+            Ok(delivery) => {
+                let delivery_send = self.delivery_send.clone();
+                thread::spawn(move || {
+                    let result = await_delivery(delivery).map_err(|err| err.to_string());
+                    let _ = delivery_send.send((topic, result));
+                });
+                self.layout.lock().producer_layout.clear_payload();
+            },
+            Err(err) => self.notify(Level::Error, format!("unable to produce to {}: {}", topic, err)),
+        }
+    }
+
+    // Show the deliveries completed since the last tick, failed deliveries are notified
+    fn refresh_deliveries(&mut self) {
+        while let Ok((topic, result)) = self.delivery_recv.try_recv() {
+            if let Err(err) = &result {
+                self.notify(Level::Error, format!("delivery to {} failed: {}", topic, err));
+            }
+            self.layout.lock().producer_layout.push_delivery(&topic, result);
+        }
+    }
+}
+
+// Implementation block for notifications
+impl <T> App<'_, T>
+where T: ClientContext + ConsumerContext {
+    // Log the notification, display it in the status bar & add it to the notification history
+    fn notify(&self, level: Level, message: String) {
+        let notification = Notification::new(level, message);
+        notification::log(&notification);
+
+        let mut layout = self.layout.lock();
+        layout.footer_layout.notify(notification.clone());
+        layout.notifications_layout.push(notification);
+    }
+
+    // Switches to the next view or the previous view in reverse
+    fn handle_view_switch(&mut self, reverse: bool) {
+        let view = self.layout.lock().view;
+        self.select_view(if reverse { view.prev() } else { view.next() });
+    }
+
+    fn select_view(&mut self, view: View) {
+        let mut layout = self.layout.lock();
+        layout.select_view(view);
+        layout.footer_layout.update_mode(view.to_string(), self.state.read_only);
+    }
+
+    // Handles the navigation of the views other than the consumer view
+    // returns false if the consumer view or the log pane is shown, or the action is common to all views
+    fn handle_view_action(&mut self, action: Option<Action>) -> bool {
+        let mut layout = self.layout.lock();
+        if layout.show_logs {
+            return false;
+        }
+
+        match (layout.view, action) {
+            (View::Consumer, _) => return false,
+            (View::Producer, Some(Action::Select)) => {
+                drop(layout);
+                self.toggle_edit_mode(EditMode::Producing);
+            },
+            (View::Producer, Some(Action::Up | Action::ScrollMessageUp)) => layout.producer_layout.scroll_deliveries(Direction::UP),
+            (View::Producer, Some(Action::Down | Action::ScrollMessageDown)) => layout.producer_layout.scroll_deliveries(Direction::DOWN),
+            (View::Groups, Some(Action::Up)) => layout.groups_layout.handle_navigation(Direction::UP),
+            (View::Groups, Some(Action::Down)) => layout.groups_layout.handle_navigation(Direction::DOWN),
+            (View::Groups, Some(Action::ScrollMessageUp)) => layout.groups_layout.details.handle_up(),
+            (View::Groups, Some(Action::ScrollMessageDown)) => layout.groups_layout.details.handle_down(),
+            (View::Admin, Some(Action::Up)) => layout.admin_layout.brokers.handle_up(),
+            (View::Admin, Some(Action::Down)) => layout.admin_layout.brokers.handle_down(),
+            (View::Admin, Some(Action::ScrollMessageUp)) => layout.admin_layout.topics.handle_up(),
+            (View::Admin, Some(Action::ScrollMessageDown)) => layout.admin_layout.topics.handle_down(),
+            // actions of the consumer view are ignored in the other views
+            (_, Some(Action::NextList | Action::PrevList | Action::Up | Action::Down | Action::Filter
+                | Action::ScrollMessageUp | Action::ScrollMessageDown | Action::NextOffset | Action::PrevOffset | Action::Back | Action::Forward
+                | Action::ZoomList | Action::ZoomMessage | Action::GrowLists | Action::ShrinkLists | Action::ToggleLayout
+                | Action::ToggleInternalTopics | Action::ToggleStreamTopics | Action::GroupTopics | Action::Select | Action::Sort
+                | Action::NewSession | Action::CloseSession | Action::NextSession | Action::Bookmark | Action::DeleteBookmark
+                | Action::CopyMessage | Action::CopyKey | Action::CopyPayload | Action::CopyHeaders | Action::CopyJson | Action::CopyKcat
+                | Action::OpenEditor | Action::OpenPager | Action::OpenPayload)) => (),
+            _ => return false,
+        }
+
+        true
+    }
+
+    // Scrolls the help, the notification history or the log pane if shown
+    // returns false if none is shown or the action is not a scroll
+    fn handle_pane_scroll(&mut self, action: Option<Action>) -> bool {
+        let direction = match action {
+            Some(Action::Up | Action::ScrollMessageUp) => Direction::UP,
+            Some(Action::Down | Action::ScrollMessageDown) => Direction::DOWN,
+            _ => return false,
+        };
+
+        let mut layout = self.layout.lock();
+        if layout.show_help {
+            match direction {
+                Direction::UP => layout.help_layout.handle_up(),
+                _ => layout.help_layout.handle_down(),
+            }
+            return true;
+        }
+
+        match (layout.show_notifications, layout.show_logs, direction) {
+            (true, _, Direction::UP) => layout.notifications_layout.handle_up(),
+            (true, _, _) => layout.notifications_layout.handle_down(),
+            (false, true, Direction::UP) => layout.logs_layout.handle_up(),
+            (false, true, _) => layout.logs_layout.handle_down(),
+            (false, false, _) => return false,
+        }
+
+        true
+    }
+}
+
+// Implementation block for changes to the layout
+impl <T> App<'_, T>
+where T: ClientContext + ConsumerContext {
+    // update the layout settings and persist them for the next session
+    fn update_layout_settings(&mut self, update: fn(&mut LayoutSettings)) {
+        let settings = {
+            let mut layout = self.layout.lock();
+            update(&mut layout.main_layout.settings);
+            layout.main_layout.settings
+        };
+
+        self.notify(Level::Info, format!("layout changed to {}, lists take {}%", settings.orientation, settings.lists_size));
+        settings.save();
+    }
+
+    // Update the topics shown in the topics list & how they are displayed
+    fn update_topic_settings(&mut self, update: fn(&mut LayoutSettings)) {
+        let settings = self.update_list_settings(update);
+        let shown = |hidden: bool| if hidden { "hidden" } else { "shown" };
+        self.notify(Level::Info, format!("internal topics {}, stream topics {}, topics {}",
+            shown(settings.hide_internal_topics), shown(settings.hide_stream_topics), if settings.group_topics { "grouped" } else { "listed flat" }));
+    }
+
+    // Cycle the order of the selected list, only topics & consumer groups can be sorted
+    fn handle_sort(&mut self) {
+        let list_name = self.layout.lock().main_layout.lists_layout.selected_list().name().to_string();
+        let settings = match list_name.as_str() {
+            TOPICS_LIST => self.update_list_settings(LayoutSettings::cycle_topic_sort),
+            CONSUMER_GROUPS_LIST => self.update_list_settings(LayoutSettings::cycle_group_sort),
+            _ => {
+                self.notify(Level::Warn, "only topics & consumer groups can be sorted".to_string());
+                return;
+            }
+        };
+
+        let sort = if list_name == TOPICS_LIST { settings.topic_sort.to_string() } else { settings.group_sort.to_string() };
+        self.notify(Level::Info, format!("{} sorted by {}", list_name.to_lowercase(), sort));
+    }
+
+    // Update the settings of the lists & refresh the lists accordingly, the settings are persisted
+    fn update_list_settings(&mut self, update: fn(&mut LayoutSettings)) -> LayoutSettings {
+        let (metadata, activity) = {
+            let consumer = self.kafka_consumer.lock();
+            (consumer.metadata().clone(), consumer.activity().clone())
+        };
+
+        let settings = {
+            let mut layout = self.layout.lock();
+            update(&mut layout.main_layout.settings);
+            layout.main_layout.refresh_lists(&metadata, &activity);
+            layout.main_layout.settings
+        };
+
+        settings.save();
+        settings
+    }
+}
+
+// Implementation block for all mouse events
+impl <T> App<'_, T>
+where T: ClientContext + ConsumerContext {
+    // Handles mouse events by hit-testing the areas of the widgets
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) {
+        let position = Position::new(mouse.column, mouse.row);
+
+        // popups cover the lists & the message pane, wheel navigates the popup & clicks are ignored
+        if self.layout.lock().popup_shown() {
+            self.handle_popup_mouse_event(mouse.kind);
+            return;
+        }
+
+        // clicking a tab switches to the view
+        if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
+            let clicked = {
+                let mut layout = self.layout.lock();
+                layout.tabs.contains(position) && layout.tabs.handle_click(position)
+            };
+
+            if clicked {
+                let view = View::from_idx(self.layout.lock().tabs.selected());
+                self.select_view(view);
+                return;
+            }
+        }
+
+        // wheel scrolls the log pane while it hides the lists & the message pane
+        let show_logs = self.layout.lock().show_logs;
+        if show_logs {
+            match mouse.kind {
+                MouseEventKind::ScrollDown => self.layout.lock().logs_layout.handle_down(),
+                MouseEventKind::ScrollUp => self.layout.lock().logs_layout.handle_up(),
+                _ => (),
+            }
+            return;
+        }
+
+        let view = self.layout.lock().view;
+        if view != View::Consumer {
+            self.handle_view_mouse_event(view, mouse.kind, position);
+            return;
+        }
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => self.handle_mouse_click(position),
+            MouseEventKind::Drag(MouseButton::Left) if self.state.dragging_scrollbar => {
+                self.layout.lock().main_layout.details_layout.message().handle_scrollbar_click(position);
+            },
+            MouseEventKind::Up(MouseButton::Left) => self.state.dragging_scrollbar = false,
+            MouseEventKind::ScrollDown => self.handle_mouse_scroll(position, Direction::DOWN),
+            MouseEventKind::ScrollUp => self.handle_mouse_scroll(position, Direction::UP),
+            _ => (),
+        }
+    }
+
+    fn handle_popup_mouse_event(&mut self, kind: MouseEventKind) {
+        let direction = match kind {
+            MouseEventKind::ScrollDown => Direction::DOWN,
+            MouseEventKind::ScrollUp => Direction::UP,
+            _ => return,
+        };
+
+        let mut layout = self.layout.lock();
+        if layout.show_help {
+            match direction {
+                Direction::UP => layout.help_layout.handle_up(),
+                _ => layout.help_layout.handle_down(),
+            }
+        } else if layout.show_bookmarks {
+            layout.bookmarks_layout.handle_navigation(direction);
+        } else if layout.show_history {
+            layout.history_layout.handle_navigation(direction);
+        } else if layout.show_notifications {
+            match direction {
+                Direction::UP => layout.notifications_layout.handle_up(),
+                _ => layout.notifications_layout.handle_down(),
+            }
+        }
+    }
+
+    // Clicking selects the clicked group & wheel scrolls the pane under the cursor in the views other than the consumer view
+    fn handle_view_mouse_event(&mut self, view: View, kind: MouseEventKind, position: Position) {
+        let mut layout = self.layout.lock();
+        match (view, kind) {
+            (View::Groups, MouseEventKind::Down(MouseButton::Left)) => {
+                layout.groups_layout.handle_click(position);
+            },
+            (View::Groups, MouseEventKind::ScrollDown) if layout.groups_layout.groups.contains(position) => layout.groups_layout.handle_navigation(Direction::DOWN),
+            (View::Groups, MouseEventKind::ScrollUp) if layout.groups_layout.groups.contains(position) => layout.groups_layout.handle_navigation(Direction::UP),
+            (View::Groups, MouseEventKind::ScrollDown) => layout.groups_layout.details.handle_down(),
+            (View::Groups, MouseEventKind::ScrollUp) => layout.groups_layout.details.handle_up(),
+            (View::Producer, MouseEventKind::ScrollDown) => layout.producer_layout.scroll_deliveries(Direction::DOWN),
+            (View::Producer, MouseEventKind::ScrollUp) => layout.producer_layout.scroll_deliveries(Direction::UP),
+            (View::Admin, MouseEventKind::ScrollDown) if layout.admin_layout.brokers.contains(position) => layout.admin_layout.brokers.handle_down(),
+            (View::Admin, MouseEventKind::ScrollUp) if layout.admin_layout.brokers.contains(position) => layout.admin_layout.brokers.handle_up(),
+            (View::Admin, MouseEventKind::ScrollDown) => layout.admin_layout.topics.handle_down(),
+            (View::Admin, MouseEventKind::ScrollUp) => layout.admin_layout.topics.handle_up(),
+            _ => (),
+        }
+    }
+
+    // Clicking a list focuses it and selects the clicked item
+    // clicking the message scrollbar scrolls the message
+    fn handle_mouse_click(&mut self, position: Position) {
+        let mut layout = self.layout.lock();
+
+        if layout.main_layout.message_visible() && layout.main_layout.details_layout.message().scrollbar_contains(position) {
+            layout.main_layout.details_layout.message().handle_scrollbar_click(position);
+            self.state.dragging_scrollbar = true;
+            return;
+        }
+
+        // clicking the pane of another session activates it
+        if let Some(idx) = layout.main_layout.session_at(position) && idx != self.state.active_session {
+            drop(layout);
+            self.activate_session(idx);
+            return;
+        }
+
+        if let Some(idx) = layout.main_layout.list_at(position) {
+            layout.main_layout.lists_layout.focus(idx);
+            let selected = layout.main_layout.lists_layout.selected_list_mut().handle_click(position);
+            drop(layout);
+
+            if selected {
+                self.handle_list_selection();
+            }
+        }
+    }
+
+    // Wheel scrolls the message pane or navigates the list under the cursor
+    fn handle_mouse_scroll(&mut self, position: Position, direction: Direction) {
+        let message_scrolled = {
+            let layout = self.layout.lock();
+            layout.main_layout.message_visible() && layout.main_layout.details_layout.message_ref().contains(position)
+        };
+
+        if message_scrolled {
+            self.handle_message_scroll(direction);
+            return;
+        }
+
+        let list_idx = self.layout.lock().main_layout.list_at(position);
+        if let Some(idx) = list_idx {
+            self.layout.lock().main_layout.lists_layout.focus(idx);
+            self.handle_list_navigation(direction);
+        }
+    }
+}
+
 // Implementation block for consuming messages
 impl <T> App<'_, T>
 where T: ClientContext + ConsumerContext {
@@ -340,33 +1336,37 @@ where T: ClientContext + ConsumerContext {
 
     // Write message to TUI
     fn write_message(&mut self, message: KafkaMessage) {
-                let message_timestamp = message.timestamp_or_default();
-                let message_offset = message.offset;
-                let message_payload = format!("Key: {}\n\nHeaders: {}\n\nPayload: {}",
-                    message.key_or_default(), pretty_print_headers(&message.headers), pretty_print_json(&message.payload_or_default()));
+        let message_payload = format_message(&message);
 
-        // copy to clipboard
-        if let Err(err) = self.copy_to_clipboard(&message_payload) {
-            error!("error while copying message to clipboard: {}", err);
+        // copy to clipboard only if opted in, as it overwrites whatever the user had copied
+        if self.state.auto_copy && let Err(err) = self.clipboard.copy(&message_payload) {
+            self.notify(Level::Warn, format!("error while copying message to clipboard: {}", err));
         }
 
         // write to TUI
-        info!("message fetched at offset {} of partition {}/{}: {}", message_offset, message.topic, message.partition, message_payload);
-        self.layout.lock().main_layout.details_layout.message.update_with_title(format!("Message offset:{} ts:{}", message_offset, message_timestamp), message_payload.into());
+        info!("message fetched at offset {} of partition {}/{}: {}", message.offset, message.topic, message.partition, message_payload);
+        self.show_message(&message);
+        self.session().message = Some(message);
+    }
+
+    // Display the message in the pane of the active session
+    fn show_message(&mut self, message: &KafkaMessage) {
+        let title = format!("Message {}/{} offset:{} ts:{}", message.topic, message.partition, message.offset, message.timestamp_or_default());
+        self.layout.lock().main_layout.details_layout.message().update_with_title(title, format_message(message).into());
     }
 
     // fetch message based on the parition name and offset
     fn fetch_message(&mut self, partition_str:&str, offset: i64) {
 
         // Clear the message block
-        self.layout.lock().main_layout.details_layout.message.update("".into());
+        self.layout.lock().main_layout.details_layout.message().update("".into());
 
         let mut offset = offset;
 
         let partition = match self.kafka_consumer.lock().metadata().get_partition(partition_str) {
             Some(partition) => partition,
             None => {
-                error!("Unable to get details for partition with name: {}", partition_str);
+                self.notify(Level::Error, format!("unable to get details for partition {}", partition_str));
                 return;
             }
         };
@@ -377,16 +1377,17 @@ where T: ClientContext + ConsumerContext {
             let low_watermark: i64;
 
             // Update status in message block
-            self.layout.lock().main_layout.details_layout.message.update("fetching watermarks ...".into());
+            self.layout.lock().main_layout.details_layout.message().update("fetching watermarks ...".into());
 
             // fetch watermarks for the give topic and partition id
-            match self.kafka_consumer.lock().fetch_watermarks(topic_name, partition_id) {
+            let watermarks = self.kafka_consumer.lock().fetch_watermarks(topic_name, partition_id);
+            match watermarks {
                 Ok((l, h)) => {
                     low_watermark = l;
                     high_watermark = h;
                 },
                 Err(err) => {
-                    error!("error while fetching watermark on topic {}/{}: {}", topic_name, partition_id, err);
+                    self.log_error_and_update(format!("error while fetching watermarks on partition {}/{}: {}", topic_name, partition_id, err));
                     return;
                 }
             };
@@ -406,25 +1407,29 @@ where T: ClientContext + ConsumerContext {
                 // set offset to the end based on HWM
                 offset = high_watermark - 1;
             } else if  offset < low_watermark || offset >= high_watermark {
-                self.layout.lock().footer_layout.set_value(ERR_INVALID_OFFSET);
                 self.log_error_and_update(format!("invalid offset {}, should be between {} and {}", offset, low_watermark, high_watermark));
                 return;
             }
 
             // Assign current partition to consumer
-            self.layout.lock().main_layout.details_layout.message.update("assigning partition ...".into());
+            self.layout.lock().main_layout.details_layout.message().update("assigning partition ...".into());
             if let Err(err) = self.assign_and_poll(topic_name, partition_id) {
                 self.log_error_and_update(format!("error assigning and polling for partition {}/{}: {}", topic_name, partition_id, err));
                 return;
             }
 
             // seek high watermark -1 by default and consume the message
-            self.layout.lock().main_layout.details_layout.message.update("seeking offset & fetching message ...".into());
+            self.layout.lock().main_layout.details_layout.message().update("seeking offset & fetching message ...".into());
             if let Some(msg) = self.seek_and_consume(topic_name, partition_id, offset) {
+                let key = msg.key.clone();
                 self.write_message(msg);
 
-                // Update offset in the state after fetching the msg successfully
-                self.state.offset = offset;
+                // Update the session after fetching the msg successfully
+                let session = self.session();
+                session.partition = Some(partition_str.to_string());
+                session.offset = offset;
+                session.history.visit(Location::new(partition_str, offset, key));
+                self.refresh_history();
             } else {
                 self.log_error_and_update(format!("no message was returned"));
                 return;
@@ -432,20 +1437,66 @@ where T: ClientContext + ConsumerContext {
         }
     }
 
-    // Copy message to clipboard
-    fn copy_to_clipboard(&mut self, message: &str) -> Result<(), Box<dyn std::error::Error>> {
-        match &mut self.clipboard {
-            Some(cb) => cb.set_text(message.to_string())?,
-            None => (),
+    // Open the message of the active session in an external program, e.g. to search through large payloads
+    // the formatted message or the payload as it was produced is written to a temp file
+    fn handle_open(&mut self, program: Program, raw: bool) {
+        let Some(message) = self.session().message.clone() else {
+            self.notify(Level::Warn, "no message to open, fetch a message first".to_string());
+            return;
+        };
+
+        let name = format!("{}-{}-{}", message.topic, message.partition, message.offset);
+        let file = if raw {
+            let Some(payload) = message.raw_payload() else {
+                self.notify(Level::Warn, format!("message at offset {} has no payload", message.offset));
+                return;
+            };
+
+            let extension = if serde_json::from_slice::<serde_json::Value>(payload).is_ok() { "json" } else { "bin" };
+            ExternalFile::new(program, &format!("{}.{}", name, extension), payload)
+        } else {
+            ExternalFile::new(program, &format!("{}.txt", name), format_message(&message).as_bytes())
+        };
+
+        match file {
+            Ok(file) => {
+                let _ = self.external_send.send(file);
+            },
+            Err(err) => self.notify(Level::Error, format!("error while writing the message for the {}: {}", program, err)),
         }
-        
-        Ok(())
     }
 
-    // log error and update TUI
+    // Copy the given content of the message of the active session to clipboard
+    fn handle_copy(&mut self, content: CopyContent) {
+        let Some(message) = self.session().message.clone() else {
+            self.notify(Level::Warn, "no message to copy, fetch a message first".to_string());
+            return;
+        };
+
+        let text = match content {
+            CopyContent::Message => Some(format_message(&message)),
+            CopyContent::Key => message.key.clone(),
+            CopyContent::Payload => message.payload.clone(),
+            CopyContent::Headers => Some(pretty_print_headers(&message.headers)),
+            CopyContent::Json => serde_json::to_string_pretty(&message.to_json()).ok(),
+            CopyContent::Kcat => Some(kcat_command(&message, &self.connection_info)),
+        };
+
+        let Some(text) = text else {
+            self.notify(Level::Warn, format!("message at offset {} has no {}", message.offset, content));
+            return;
+        };
+
+        match self.clipboard.copy(&text) {
+            Ok(target) => self.notify(Level::Info, format!("copied {} to the {}", content, target)),
+            Err(err) => self.notify(Level::Error, format!("error while copying {} to clipboard: {}", content, err)),
+        }
+    }
+
+    // log error, notify and update TUI
     fn log_error_and_update(&mut self, message: String) {
-        error!("{}", message);
-        self.layout.lock().main_layout.details_layout.message.update(message.into());
+        self.layout.lock().main_layout.details_layout.message().update(message.clone().into());
+        self.notify(Level::Error, message);
     }
 }
 
@@ -458,18 +1509,94 @@ where T: ClientContext + ConsumerContext {
             EditMode::Normal => {
                 //self.layout.lock().footer_layout.handle_input_event(InputEvent::Reset);
                 self.state.edit_mode = EditMode::Normal;
+                self.layout.lock().producer_layout.set_editing(false);
             },
             EditMode::Editing => {
                 self.state.edit_mode = EditMode::Editing;
+                self.command_history.reset();
                 self.layout.lock().footer_layout.handle_input_event(InputEvent::Reset);
-                self.layout.lock().footer_layout.handle_input_event(InputEvent::NewChar(':'));
-            }
+                self.handle_input_event(InputEvent::NewChar(':'));
+            },
+            EditMode::Filtering => {
+                // start with an empty filter or continue editing the existing filter
+                self.state.edit_mode = EditMode::Filtering;
+                let mut layout = self.layout.lock();
+                if layout.show_logs {
+                    if layout.logs_layout.search().is_none() {
+                        layout.logs_layout.set_search(Some("".to_string()));
+                    }
+                    return;
+                }
+
+                let list = layout.main_layout.lists_layout.selected_list_mut();
+                if list.filter().is_none() {
+                    list.set_filter(Some("".to_string()));
+                }
+            },
+            EditMode::Producing => {
+                if self.producer.is_none() {
+                    self.notify(Level::Warn, "producing messages is disabled in read-only mode".to_string());
+                    return;
+                }
+
+                self.state.edit_mode = EditMode::Producing;
+                self.layout.lock().producer_layout.set_editing(true);
+            },
         }
     }   
 
     // Handle input event
     fn handle_input_event(&mut self, input_event: InputEvent) {
         self.layout.lock().footer_layout.handle_input_event(input_event);
+        self.update_command_hint();
+    }
+
+    // Complete the command name, topic or partition being typed
+    fn handle_command_completion(&mut self) {
+        let input = self.layout.lock().footer_layout.input_value();
+        let (topics, partitions) = self.completion_candidates();
+
+        if let Some(completed) = command::complete(&input, &topics, &partitions) {
+            self.layout.lock().footer_layout.set_value(&completed);
+            self.update_command_hint();
+        }
+    }
+
+    // Replace the input with the previous or the next command in the history
+    fn handle_command_history(&mut self, direction: Direction) {
+        let command = match direction {
+            Direction::UP => self.command_history.previous(),
+            Direction::DOWN => self.command_history.next(),
+            _ => None,
+        };
+
+        if let Some(command) = command {
+            let command = if command.is_empty() { ":" } else { command };
+            self.layout.lock().footer_layout.set_value(command);
+            self.update_command_hint();
+        }
+    }
+
+    fn update_command_hint(&mut self) {
+        let input = self.layout.lock().footer_layout.input_value();
+        let (topics, partitions) = self.completion_candidates();
+        let hint = command::hint(&input, &topics, &partitions);
+        self.layout.lock().footer_layout.set_input_hint(hint);
+    }
+
+    // topics along with the partition ids of the selected topic
+    fn completion_candidates(&mut self) -> (Vec<String>, Vec<String>) {
+        let mut layout = self.layout.lock();
+        let topics = layout.main_layout.lists_layout.get_list_by_name(TOPICS_LIST)
+            .map(|l| l.items().to_vec())
+            .unwrap_or_default();
+        let partitions = layout.main_layout.lists_layout.get_list_by_name(PARTITIONS_LIST)
+            .map(|l| l.items().iter()
+                .filter_map(|p| get_topic_and_parition_id(p).map(|(_, id)| id.to_string()))
+                .collect())
+            .unwrap_or_default();
+
+        (topics, partitions)
     }
 
     // handle input submission
@@ -481,29 +1608,33 @@ where T: ClientContext + ConsumerContext {
         self.handle_command(&input_value)
     }
 
-    // validate cmd
+    // parse & validate cmd
     fn handle_command(&mut self, input: &str)  {
-
-        let inputs = input.split("!").collect::<Vec<&str>>();
-        if inputs.len() < 2 {
-            self.layout.lock().footer_layout.set_value(ERR_INVALID_CMD);
-            error!("invalid command {}: command should be of format :<command>!<arg>", input);
-            return;
+        if input.trim() != ":" {
+            self.command_history.push(input.trim());
         }
 
-        let (command, arg) = match Command::from_str(inputs[0]) {
-           Ok(cmd) => (cmd, inputs[1]),
+        let command = match Command::from_str(input) {
+           Ok(cmd) => cmd,
            Err(err) => {
-               self.layout.lock().footer_layout.set_value(ERR_INVALID_CMD);
-               error!("error parsing command {}: {}", input, err);
+               self.notify(Level::Error, format!("invalid command {}: {}", input, err));
                return;
            }
        };
 
+       // commands act on the consumer view
+       self.select_view(View::Consumer);
+
        match command {
-           Command::Invalid => return,
-           Command::Offset => self.handle_offset_command(arg),
-           Command::Timestamp => self.handle_timestamp_command(arg),
+           Command::Offset(offset) => self.handle_offset_command(offset),
+           Command::Timestamp(timestamp) => self.handle_timestamp_command(timestamp),
+           Command::Topic(topic) => self.handle_topic_command(&topic),
+           Command::Partition(partition_id) => self.handle_partition_command(partition_id),
+           Command::Bookmark(note) => self.handle_bookmark_command(&note),
+           Command::ExportBookmarks(file) => self.handle_export_bookmarks_command(&file),
+           Command::ImportBookmarks(file) => self.handle_import_bookmarks_command(&file),
+           Command::Export { from, to, file, format } => self.handle_export_command(from, to, &file, format),
+           Command::CancelExport => self.handle_cancel_export_command(),
        }
     }
 }
@@ -512,22 +1643,11 @@ where T: ClientContext + ConsumerContext {
 impl <T> App<'_, T>
 where T: ClientContext + ConsumerContext {
     // Handle offset command
-    pub fn handle_offset_command(&mut self, offset_str: &str)  {
-        //check if offset is a number
-        let offset = match offset_str.parse::<i64>() {
-            Ok(o) => o,
-            Err(_) => {
-                self.layout.lock().footer_layout.set_value(ERR_INVALID_OFFSET);
-                error!("invalid offset {}", offset_str);
-                return;
-            }
-        };
-
+    pub fn handle_offset_command(&mut self, offset: i64)  {
         let selected_partition = match self.get_selected_item_for_list(PARTITIONS_LIST) {
             Some(p) => p,
             None => {
-                self.layout.lock().footer_layout.set_value(ERR_NO_SELECTED_PARTITION);
-                error!("no partition selected to seek");
+                self.notify(Level::Error, "no partition selected to seek, select a partition first".to_string());
                 return;
             }
         };
@@ -536,30 +1656,12 @@ where T: ClientContext + ConsumerContext {
     }
 
     // handle timestamp command
-    pub fn handle_timestamp_command(&mut self, timestamp_str: &str)  {
-        //check if timestamp is a number
-        let _timestamp = match timestamp_str.parse::<i64>() {
-            Ok(t) => t,
-            Err(_) => {
-                self.layout.lock().footer_layout.set_value(ERR_INVALID_TIMESTAMP);
-                error!("invalid timestamp {}. timestamp should be a number representing an epoch in milliseconds", timestamp_str);
-                return;
-            }
-        };
-
-        // check if it is a valid epoch timestamp
-        if DateTime::from_timestamp_millis(_timestamp) == None {
-            self.layout.lock().footer_layout.set_value(ERR_INVALID_TIMESTAMP);
-            error!("invalid timestamp {}. timestamp should be an epoch in milliseconds", timestamp_str);
-            return;
-        }
-
+    pub fn handle_timestamp_command(&mut self, _timestamp: i64)  {
         // fetch offset for a given timestamp
         let selected_partition = match self.get_selected_item_for_list(PARTITIONS_LIST) {
             Some(p) => p,
             None => {
-            self.layout.lock().footer_layout.set_value(ERR_NO_SELECTED_PARTITION);
-            error!("no partition selected to seek");
+            self.notify(Level::Error, "no partition selected to seek, select a partition first".to_string());
             return;
             }
         };
@@ -570,14 +1672,12 @@ where T: ClientContext + ConsumerContext {
                 Ok(offset) => match offset {
                     Some(o) => o,
                     None => {
-                        self.layout.lock().footer_layout.set_value(ERR_OFFSET_NOT_FOUND);
-                        error!("no offset found for topic {} & partition {} for timestamp {}", topic_name, partition_id, _timestamp);
+                        self.notify(Level::Error, format!("no offset found for topic {} & partition {} for timestamp {}", topic_name, partition_id, _timestamp));
                         return;
                     }
                 },
                 Err(err) => {
-                    self.layout.lock().footer_layout.set_value(ERR_FETCHING_OFFSET);
-                    error!("error fetching offset for timestamp {}: {}", _timestamp, err);
+                    self.notify(Level::Error, format!("error fetching offset for timestamp {}: {}", _timestamp, err));
                     return;
                 }
             };
@@ -586,20 +1686,70 @@ where T: ClientContext + ConsumerContext {
         }
     }
 
+    // Select the topic, the filter of the topics list is cleared if it hides the topic
+    pub fn handle_topic_command(&mut self, topic: &str) {
+        let selected = self.select_list_item(TOPICS_LIST, topic);
+        if selected {
+            return;
+        }
+
+        // topic may exist but be hidden from the topics list
+        if self.kafka_consumer.lock().metadata().get_topic(topic).is_some() {
+            self.notify(Level::Warn, format!("topic {} is hidden, show internal or stream topics to select it", topic));
+        } else {
+            self.notify(Level::Error, format!("topic {} not found", topic));
+        }
+    }
+
+    // Select the partition of the selected topic
+    pub fn handle_partition_command(&mut self, partition_id: i32) {
+        let selected_topic = match self.get_selected_item_for_list(TOPICS_LIST) {
+            Some(t) => t,
+            None => {
+                self.notify(Level::Error, format!("no topic selected to select partition {}, select a topic first", partition_id));
+                return;
+            }
+        };
+
+        let partition = format!("{}/{}", selected_topic, partition_id);
+        if !self.select_list_item(PARTITIONS_LIST, &partition) {
+            self.notify(Level::Error, format!("partition {} not found", partition));
+        }
+    }
+
+    // Focus the list and select the item, returns false if the item is not found
+    fn select_list_item(&mut self, list_name: &str, item: &str) -> bool {
+        {
+            let mut layout = self.layout.lock();
+            let lists_layout = &mut layout.main_layout.lists_layout;
+            let Some(idx) = lists_layout.lists.iter().position(|l| l.name() == list_name) else {
+                return false;
+            };
+
+            if !lists_layout.lists[idx].select_value(item) {
+                return false;
+            }
+
+            lists_layout.focus(idx);
+        }
+
+        self.handle_list_selection();
+        true
+    }
+
     // Handle offset navigation
     pub fn handle_offset_navigation(&mut self, direction: Direction){
         // get current offset on the topic
         let selected_partition = match self.get_selected_item_for_list(PARTITIONS_LIST) {
             Some(p) => p,
             None => {
-                self.layout.lock().footer_layout.set_value(ERR_NO_SELECTED_PARTITION);
-                error!("no partition selected to seek");
+                self.notify(Level::Error, "no partition selected to seek, select a partition first".to_string());
                 return;
             }
         };
 
         // fetch current offset from state
-        let mut offset = self.state.offset;
+        let mut offset = self.session().offset;
 
         // Increment / decrement offset based on the direction
         match direction {
@@ -615,6 +1765,24 @@ where T: ClientContext + ConsumerContext {
         let current_state = self.layout.lock().show_help;
         self.layout.lock().show_help = !current_state;
     }
+
+    pub fn handle_connection_info_command(&mut self) {
+        let current_state = self.layout.lock().show_connection_info;
+        self.layout.lock().show_connection_info = !current_state;
+    }
+
+    pub fn handle_logs_command(&mut self) {
+        let mut layout = self.layout.lock();
+        layout.show_logs = !layout.show_logs;
+        if layout.show_logs {
+            layout.logs_layout.refresh_if_following();
+        }
+    }
+
+    pub fn handle_notifications_command(&mut self) {
+        let current_state = self.layout.lock().show_notifications;
+        self.layout.lock().show_notifications = !current_state;
+    }
 }
 
 // Generate broker deatils
@@ -669,6 +1837,23 @@ fn pretty_print_json(json_str: &str) -> String {
     }
 }
 
+// Format the message for the message pane & the clipboard
+fn format_message(message: &KafkaMessage) -> String {
+    format!("Key: {}\n\nHeaders: {}\n\nPayload: {}",
+        message.key_or_default(), pretty_print_headers(&message.headers), pretty_print_json(&message.payload_or_default()))
+}
+
+// kcat command fetching the same message, credentials are to be added with -X
+fn kcat_command(message: &KafkaMessage, connection_info: &ConnectionInfo) -> String {
+    let mut command = format!("kcat -C -b {}", connection_info.bootstrap_servers);
+    if connection_info.protocol != "PLAINTEXT" {
+        command.push_str(&format!(" -X security.protocol={}", connection_info.protocol));
+    }
+
+    command.push_str(&format!(" -t {} -p {} -o {} -c 1 -e", message.topic, message.partition, message.offset));
+    command
+}
+
 // Pretty print headers
 fn pretty_print_headers(headers: &HashMap<String, String>) -> String {
     match serde_json::to_string_pretty(&headers) {
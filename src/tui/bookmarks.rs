@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+// Message bookmarked along with a note, e.g. while investigating an incident
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    // bootstrap servers of the cluster
+    pub cluster: String,
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    #[serde(default)]
+    pub note: String,
+    // local time the bookmark was added at, e.g. 2024-05-01 10:00:00
+    #[serde(default)]
+    pub created_at: String,
+}
+
+impl Bookmark {
+    pub fn new(cluster: &str, topic: &str, partition: i32, offset: i64, note: &str) -> Bookmark {
+        Bookmark {
+            cluster: cluster.to_string(),
+            topic: topic.to_string(),
+            partition,
+            offset,
+            note: note.to_string(),
+            created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+
+    // Position of the message, e.g. orders/3@1200
+    pub fn position(&self) -> String {
+        format!("{}/{}@{}", self.topic, self.partition, self.offset)
+    }
+
+    fn same_position(&self, other: &Bookmark) -> bool {
+        self.cluster == other.cluster && self.topic == other.topic && self.partition == other.partition && self.offset == other.offset
+    }
+}
+
+// Bookmarks of all the clusters, persisted between sessions
+pub struct Bookmarks {
+    entries: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    // Load the bookmarks of the previous sessions
+    pub fn load() -> Bookmarks {
+        Bookmarks {
+            entries: config::load_state(BOOKMARKS_FILE),
+        }
+    }
+
+    // Bookmarks of the cluster in the order they were added
+    pub fn of_cluster(&self, cluster: &str) -> Vec<Bookmark> {
+        self.entries.iter()
+            .filter(|b| b.cluster == cluster)
+            .cloned()
+            .collect()
+    }
+
+    // Add the bookmark, the note of an existing bookmark at the same position is replaced
+    // returns false if an existing bookmark got replaced
+    pub fn add(&mut self, bookmark: Bookmark) -> bool {
+        let added = match self.entries.iter_mut().find(|b| b.same_position(&bookmark)) {
+            Some(existing) => {
+                existing.note = bookmark.note;
+                false
+            },
+            None => {
+                self.entries.push(bookmark);
+                true
+            },
+        };
+
+        self.save();
+        added
+    }
+
+    pub fn remove(&mut self, bookmark: &Bookmark) {
+        self.entries.retain(|b| !b.same_position(bookmark));
+        self.save();
+    }
+
+    // Write the bookmarks of all the clusters as JSON, e.g. to share them with teammates
+    // returns the number of bookmarks exported
+    pub fn export(&self, path: &str) -> Result<usize, String> {
+        config::write_file(Path::new(path), &self.entries)?;
+        Ok(self.entries.len())
+    }
+
+    // Add the bookmarks exported to the file, bookmarks at the existing positions are skipped
+    // returns the number of bookmarks imported
+    pub fn import(&mut self, path: &str) -> Result<usize, String> {
+        let imported: Vec<Bookmark> = config::read_file(Path::new(path))?;
+        let before = self.entries.len();
+
+        for bookmark in imported {
+            if !self.entries.iter().any(|b| b.same_position(&bookmark)) {
+                self.entries.push(bookmark);
+            }
+        }
+
+        self.save();
+        Ok(self.entries.len() - before)
+    }
+
+    fn save(&self) {
+        config::save_state(BOOKMARKS_FILE, &self.entries);
+    }
+}
@@ -0,0 +1,85 @@
+use std::{env, error::Error, io::Write};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use log::info;
+use strum::Display;
+
+// Parts of the message which can be copied
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum CopyContent {
+    #[strum(serialize = "message")]
+    Message,
+    #[strum(serialize = "key")]
+    Key,
+    #[strum(serialize = "payload")]
+    Payload,
+    #[strum(serialize = "headers")]
+    Headers,
+    #[strum(serialize = "message as JSON")]
+    Json,
+    #[strum(serialize = "kcat command")]
+    Kcat,
+}
+
+// Clipboard the text got copied to
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum CopyTarget {
+    #[strum(serialize = "system clipboard")]
+    System,
+    #[strum(serialize = "terminal clipboard (OSC 52)")]
+    Terminal,
+}
+
+// Copies to the system clipboard, or to the clipboard of the terminal with OSC 52 escape sequences
+// when no system clipboard is available, e.g. over SSH
+pub struct Clipboard {
+    system: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    pub fn new() -> Clipboard {
+        // system clipboard of the remote host is of no use over SSH
+        if env::var_os("SSH_TTY").is_some() || env::var_os("SSH_CONNECTION").is_some() {
+            info!("running over SSH, copying to the terminal clipboard with OSC 52");
+            return Clipboard { system: None };
+        }
+
+        let system = match arboard::Clipboard::new() {
+            Ok(clipboard) => Some(clipboard),
+            Err(err) => {
+                info!("system clipboard is not available, copying to the terminal clipboard with OSC 52: {}", err);
+                None
+            }
+        };
+
+        Clipboard { system }
+    }
+
+    pub fn copy(&mut self, text: &str) -> Result<CopyTarget, Box<dyn Error>> {
+        if let Some(system) = &mut self.system {
+            match system.set_text(text.to_string()) {
+                Ok(()) => return Ok(CopyTarget::System),
+                Err(err) => info!("unable to copy to the system clipboard, falling back to OSC 52: {}", err),
+            }
+        }
+
+        copy_osc52(text)?;
+        Ok(CopyTarget::Terminal)
+    }
+}
+
+// Ask the terminal to set its clipboard, the terminal is drawn on stderr
+fn copy_osc52(text: &str) -> std::io::Result<()> {
+    let sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+
+    // tmux only passes the sequence through to the terminal when wrapped
+    let sequence = if env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    };
+
+    let mut stderr = std::io::stderr().lock();
+    stderr.write_all(sequence.as_bytes())?;
+    stderr.flush()
+}
@@ -0,0 +1,426 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use strum::{Display, EnumIter, EnumMessage, EnumString, IntoEnumIterator};
+
+use crate::config;
+use crate::kafka::consumer::OffsetBound;
+use crate::kafka::export::ExportFormat;
+
+const COMMAND_PREFIX: char = ':';
+
+const HISTORY_FILE: &str = "command_history.json";
+const HISTORY_SIZE: usize = 100;
+
+// Timestamp formats with an offset, "Z" is replaced with a zero offset before parsing
+const TIMESTAMP_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f%:z", "%Y-%m-%dT%H:%M%:z"];
+// Timestamp formats without an offset, these are considered to be in UTC
+const NAIVE_TIMESTAMP_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"];
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// Names of the commands which can be entered in edit mode
+#[derive(Clone, Copy, Debug, Display, PartialEq, EnumIter, EnumString, EnumMessage)]
+#[strum(serialize_all = "lowercase")]
+pub enum CommandName {
+    #[strum(message = "<offset>", detailed_message = "Fetches the message at a given offset")]
+    Offset,
+    #[strum(message = "<epoch_ms|iso8601>", detailed_message = "Fetches the first message at or after a given timestamp")]
+    Ts,
+    #[strum(message = "<name>", detailed_message = "Selects the topic with the given name")]
+    Topic,
+    #[strum(message = "<id>", detailed_message = "Selects the partition of the selected topic")]
+    Partition,
+    #[strum(message = "[note]", detailed_message = "Bookmarks the message along with a note")]
+    Bookmark,
+    #[strum(serialize = "export-bookmarks", message = "<file>", detailed_message = "Exports the bookmarks as JSON")]
+    ExportBookmarks,
+    #[strum(serialize = "import-bookmarks", message = "<file>", detailed_message = "Imports the bookmarks exported as JSON")]
+    ImportBookmarks,
+    #[strum(message = "<from> <to> <file>", detailed_message = "Exports the messages of the selected partition between offsets, ISO 8601 timestamps or start & end to .jsonl, .csv or .bin")]
+    Export,
+    #[strum(serialize = "cancel-export", detailed_message = "Cancels the running export")]
+    CancelExport,
+}
+
+impl CommandName {
+    pub fn usage(&self) -> &'static str {
+        self.get_message().unwrap_or_default()
+    }
+
+    pub fn description(&self) -> &'static str {
+        self.get_detailed_message().unwrap_or_default()
+    }
+}
+
+// Parsed command along with its argument
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Offset(i64),
+    // epoch in milliseconds
+    Timestamp(i64),
+    Topic(String),
+    Partition(i32),
+    // note of the bookmark, may be empty
+    Bookmark(String),
+    // file to export the bookmarks to or to import them from
+    ExportBookmarks(String),
+    ImportBookmarks(String),
+    // range of the selected partition, the message at the end is not exported
+    Export {
+        from: OffsetBound,
+        to: OffsetBound,
+        file: String,
+        format: ExportFormat,
+    },
+    CancelExport,
+}
+
+#[derive(Debug)]
+pub struct CommandError {
+    message: String
+}
+
+impl CommandError {
+    fn new(message: &str) -> CommandError {
+        CommandError {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for CommandError {}
+
+// Parses commands of format :<command> <arg>, e.g. ":offset 1234"
+impl FromStr for Command {
+    type Err = CommandError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim()
+            .strip_prefix(COMMAND_PREFIX)
+            .ok_or(CommandError::new("command should be of format :<command> <arg>"))?;
+
+        let (name, arg) = match input.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, arg.trim()),
+            None => (input, ""),
+        };
+
+        let name = CommandName::from_str(name)
+            .map_err(|_| CommandError::new(&format!("unknown command :{}", name)))?;
+
+        // note of the bookmark is optional & cancelling the export takes no argument
+        if arg.is_empty() && !matches!(name, CommandName::Bookmark | CommandName::CancelExport) {
+            return Err(CommandError::new(&format!("missing argument, usage :{} {}", name, name.usage())));
+        }
+
+        match name {
+            CommandName::Offset => arg.parse::<i64>()
+                .map(Command::Offset)
+                .map_err(|_| CommandError::new(&format!("invalid offset {}, offset should be a number", arg))),
+            CommandName::Ts => parse_timestamp(arg).map(Command::Timestamp),
+            CommandName::Topic => Ok(Command::Topic(arg.to_string())),
+            CommandName::Partition => arg.parse::<i32>()
+                .map(Command::Partition)
+                .map_err(|_| CommandError::new(&format!("invalid partition {}, partition should be a number", arg))),
+            CommandName::Bookmark => Ok(Command::Bookmark(arg.to_string())),
+            CommandName::ExportBookmarks => Ok(Command::ExportBookmarks(arg.to_string())),
+            CommandName::ImportBookmarks => Ok(Command::ImportBookmarks(arg.to_string())),
+            CommandName::Export => parse_export(arg),
+            CommandName::CancelExport => Ok(Command::CancelExport),
+        }
+    }
+}
+
+// Parses the range & the file of the export, e.g. "100 end orders.jsonl"
+fn parse_export(arg: &str) -> Result<Command, CommandError> {
+    let usage = || CommandError::new(&format!("usage :{} {}", CommandName::Export, CommandName::Export.usage()));
+    let (from, rest) = arg.split_once(char::is_whitespace).ok_or_else(usage)?;
+    let (to, file) = rest.trim().split_once(char::is_whitespace).ok_or_else(usage)?;
+    let file = file.trim();
+
+    let format = ExportFormat::from_path(file)
+        .ok_or(CommandError::new(&format!("unsupported file {}, file should end with .jsonl, .csv or .bin", file)))?;
+
+    Ok(Command::Export {
+        from: parse_bound(from)?,
+        to: parse_bound(to)?,
+        file: file.to_string(),
+        format,
+    })
+}
+
+// Numbers are offsets as epoch timestamps would be ambiguous, start & end are the watermarks of the partition
+fn parse_bound(bound: &str) -> Result<OffsetBound, CommandError> {
+    match bound {
+        "start" => Ok(OffsetBound::Offset(0)),
+        "end" => Ok(OffsetBound::Offset(i64::MAX)),
+        _ => match bound.parse::<i64>() {
+            Ok(offset) => Ok(OffsetBound::Offset(offset)),
+            Err(_) => parse_timestamp(bound).map(OffsetBound::Timestamp),
+        },
+    }
+}
+
+// Parses epoch in milliseconds or ISO 8601 timestamps, e.g. 2024-05-01T10:00Z
+pub fn parse_timestamp(timestamp: &str) -> Result<i64, CommandError> {
+    if let Ok(millis) = timestamp.parse::<i64>() {
+        return DateTime::from_timestamp_millis(millis)
+            .map(|_| millis)
+            .ok_or(CommandError::new(&format!("invalid timestamp {}, epoch in milliseconds is out of range", timestamp)));
+    }
+
+    let with_offset = match timestamp.strip_suffix(['Z', 'z']) {
+        Some(t) => format!("{}+00:00", t),
+        None => timestamp.to_string(),
+    };
+
+    if let Some(dt) = TIMESTAMP_FORMATS.iter().find_map(|f| DateTime::parse_from_str(&with_offset, f).ok()) {
+        return Ok(dt.timestamp_millis());
+    }
+
+    if let Some(dt) = NAIVE_TIMESTAMP_FORMATS.iter().find_map(|f| NaiveDateTime::parse_from_str(timestamp, f).ok()) {
+        return Ok(dt.and_utc().timestamp_millis());
+    }
+
+    NaiveDate::parse_from_str(timestamp, DATE_FORMAT).ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp_millis())
+        .ok_or(CommandError::new(&format!("invalid timestamp {}, timestamp should be an epoch in milliseconds or ISO 8601, e.g. 2024-05-01T10:00Z", timestamp)))
+}
+
+// Word being typed along with the candidates to complete it
+// command names are completed first, followed by topic names & partitions for the respective commands
+fn candidates(input: &str, topics: &[String], partitions: &[String]) -> Option<(String, Vec<String>)> {
+    let input = input.strip_prefix(COMMAND_PREFIX)?;
+    match input.split_once(' ') {
+        None => Some((input.to_string(), CommandName::iter().map(|c| c.to_string()).collect())),
+        Some((name, arg)) => match CommandName::from_str(name).ok()? {
+            CommandName::Topic => Some((arg.to_string(), topics.to_vec())),
+            CommandName::Partition => Some((arg.to_string(), partitions.to_vec())),
+            _ => None,
+        },
+    }
+}
+
+// Completes the word being typed up to the longest common prefix of the matching candidates
+pub fn complete(input: &str, topics: &[String], partitions: &[String]) -> Option<String> {
+    let (word, candidates) = candidates(input, topics, partitions)?;
+    let matches = candidates.iter()
+        .filter(|c| c.starts_with(&word))
+        .collect::<Vec<&String>>();
+
+    let completed = match matches.as_slice() {
+        [] => return None,
+        // command name is completed along with the separator for the argument
+        [single] if !input.contains(' ') => format!("{} ", single),
+        [single] => single.to_string(),
+        _ => common_prefix(&matches),
+    };
+
+    if completed.len() <= word.len() {
+        return None;
+    }
+
+    Some(format!("{}{}", &input[..input.len() - word.len()], completed))
+}
+
+// Inline hint displayed after the input, either the usage of the command or the rest of the first match
+pub fn hint(input: &str, topics: &[String], partitions: &[String]) -> Option<String> {
+    let command = input.strip_prefix(COMMAND_PREFIX)?;
+    match command.split_once(' ') {
+        None => {
+            let matches = CommandName::iter()
+                .filter(|c| c.to_string().starts_with(command))
+                .collect::<Vec<CommandName>>();
+
+            match matches.as_slice() {
+                [] => None,
+                [name] => Some(format!("{} {}", &name.to_string()[command.len()..], name.usage())),
+                _ => Some(format!("  {}", matches.iter().map(|c| c.to_string()).collect::<Vec<String>>().join("|"))),
+            }
+        },
+        Some((name, arg)) => {
+            let name = CommandName::from_str(name).ok()?;
+            if arg.is_empty() {
+                return Some(name.usage().to_string());
+            }
+
+            let (word, candidates) = candidates(input, topics, partitions)?;
+            candidates.iter()
+                .find(|c| c.starts_with(&word) && c.len() > word.len())
+                .map(|c| c[word.len()..].to_string())
+        },
+    }
+}
+
+fn common_prefix(values: &[&String]) -> String {
+    let mut prefix = values[0].to_string();
+    for value in &values[1..] {
+        while !value.starts_with(&prefix) {
+            prefix.pop();
+        }
+    }
+
+    prefix
+}
+
+// History of the submitted commands, persisted between sessions
+pub struct CommandHistory {
+    entries: Vec<String>,
+    // position while browsing the history, None when not browsing
+    position: Option<usize>,
+}
+
+impl CommandHistory {
+    // Load the history of the previous sessions
+    pub fn load() -> CommandHistory {
+        CommandHistory {
+            entries: config::load_state(HISTORY_FILE),
+            position: None,
+        }
+    }
+
+    // Add the command to the history, repeated commands are stored once
+    pub fn push(&mut self, command: &str) {
+        self.position = None;
+        if self.entries.last().is_some_and(|e| e == command) {
+            return;
+        }
+
+        self.entries.push(command.to_string());
+        if self.entries.len() > HISTORY_SIZE {
+            self.entries.remove(0);
+        }
+
+        self.save();
+    }
+
+    // Previous command in the history, stays at the oldest command
+    pub fn previous(&mut self) -> Option<&str> {
+        let position = match self.position {
+            Some(p) => p.saturating_sub(1),
+            None => self.entries.len().checked_sub(1)?,
+        };
+
+        self.position = Some(position);
+        self.entries.get(position).map(|e| e.as_str())
+    }
+
+    // Next command in the history, empty after the latest command
+    // None if the history is not being browsed
+    pub fn next(&mut self) -> Option<&str> {
+        let position = self.position? + 1;
+        if position >= self.entries.len() {
+            self.position = None;
+            return Some("");
+        }
+
+        self.position = Some(position);
+        self.entries.get(position).map(|e| e.as_str())
+    }
+
+    pub fn reset(&mut self) {
+        self.position = None;
+    }
+
+    fn save(&self) {
+        config::save_state(HISTORY_FILE, &self.entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_timestamp_of_epoch() {
+        assert_eq!(parse_timestamp("1714557600000").unwrap(), 1714557600000);
+        assert!(parse_timestamp(&i64::MAX.to_string()).is_err());
+    }
+
+    #[test]
+    fn parse_timestamp_of_iso_8601() {
+        assert_eq!(parse_timestamp("2024-05-01T10:00Z").unwrap(), 1714557600000);
+        assert_eq!(parse_timestamp("2024-05-01T10:00:00z").unwrap(), 1714557600000);
+        assert_eq!(parse_timestamp("2024-05-01T10:00:00.5+02:00").unwrap(), 1714550400500);
+        // without an offset the timestamp is in UTC
+        assert_eq!(parse_timestamp("2024-05-01T10:00").unwrap(), 1714557600000);
+        assert_eq!(parse_timestamp("2024-05-01").unwrap(), 1714521600000);
+    }
+
+    #[test]
+    fn parse_timestamp_of_malformed_input() {
+        for timestamp in ["", "yesterday", "2024-13-01", "2024-05-01T25:00Z", "2024-05-01 10:00", "1714557600000ms"] {
+            assert!(parse_timestamp(timestamp).is_err(), "{} should be invalid", timestamp);
+        }
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(Command::from_str(":offset 1234").unwrap(), Command::Offset(1234));
+        assert_eq!(Command::from_str("  :ts 2024-05-01T10:00Z ").unwrap(), Command::Timestamp(1714557600000));
+        assert_eq!(Command::from_str(":topic orders").unwrap(), Command::Topic("orders".to_string()));
+        assert_eq!(Command::from_str(":partition 3").unwrap(), Command::Partition(3));
+        assert_eq!(Command::from_str(":bookmark").unwrap(), Command::Bookmark(String::new()));
+        assert_eq!(Command::from_str(":bookmark  slow consumer ").unwrap(), Command::Bookmark("slow consumer".to_string()));
+        assert_eq!(Command::from_str(":export-bookmarks b.json").unwrap(), Command::ExportBookmarks("b.json".to_string()));
+        assert_eq!(Command::from_str(":cancel-export").unwrap(), Command::CancelExport);
+    }
+
+    #[test]
+    fn parse_export_commands() {
+        assert_eq!(Command::from_str(":export 100 end orders.jsonl").unwrap(), Command::Export {
+            from: OffsetBound::Offset(100),
+            to: OffsetBound::Offset(i64::MAX),
+            file: "orders.jsonl".to_string(),
+            format: ExportFormat::Jsonl,
+        });
+        assert_eq!(Command::from_str(":export start 2024-05-01T10:00Z orders.csv").unwrap(), Command::Export {
+            from: OffsetBound::Offset(0),
+            to: OffsetBound::Timestamp(1714557600000),
+            file: "orders.csv".to_string(),
+            format: ExportFormat::Csv,
+        });
+    }
+
+    #[test]
+    fn parse_malformed_commands() {
+        for input in ["offset 1234", ":", ":unknown 1", ":offset", ":offset abc", ":partition 1.5", ":ts tomorrow",
+            ":export 1 2", ":export 1 2 orders.txt", ":export first end orders.jsonl"] {
+            assert!(Command::from_str(input).is_err(), "{} should be invalid", input);
+        }
+    }
+
+    #[test]
+    fn complete_command_names() {
+        assert_eq!(complete(":o", &[], &[]), Some(":offset ".to_string()));
+        assert_eq!(complete(":to", &[], &[]), Some(":topic ".to_string()));
+        // completed up to the common prefix of export & export-bookmarks
+        assert_eq!(complete(":e", &[], &[]), Some(":export".to_string()));
+        assert_eq!(complete(":t", &[], &[]), None);
+        assert_eq!(complete(":x", &[], &[]), None);
+        assert_eq!(complete("offset", &[], &[]), None);
+    }
+
+    #[test]
+    fn complete_arguments() {
+        let topics = strings(&["orders", "orders-dlq", "payments"]);
+        let partitions = strings(&["0", "1", "10"]);
+
+        assert_eq!(complete(":topic p", &topics, &partitions), Some(":topic payments".to_string()));
+        assert_eq!(complete(":topic o", &topics, &partitions), Some(":topic orders".to_string()));
+        assert_eq!(complete(":topic orders", &topics, &partitions), None);
+        assert_eq!(complete(":partition 1", &topics, &partitions), None);
+        assert_eq!(complete(":partition ", &topics, &partitions), None);
+        assert_eq!(complete(":offset 1", &topics, &partitions), None);
+    }
+}
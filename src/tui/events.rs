@@ -1,11 +1,13 @@
+use std::sync::mpsc as std_mpsc;
+
 use futures::{StreamExt, FutureExt};
 use tokio::{
-    sync::mpsc,
+    sync::{mpsc, watch},
     task::JoinHandle,
     runtime::Builder,
 };
 use color_eyre::Result;
-use crossterm::event::{self, KeyEvent};
+use crossterm::event::{self, KeyEvent, MouseEvent};
 
 /// Terminal events.
 #[derive(Clone, Copy, Debug)]
@@ -14,6 +16,7 @@ pub enum TuiEvent {
     Tick,
     Render,
     Key(KeyEvent),
+    Mouse(MouseEvent),
 }
 
 /// Terminal event handler.
@@ -25,6 +28,10 @@ pub struct EventHandler {
     rx: mpsc::UnboundedReceiver<TuiEvent>,
     /// Event handler thread.
     task: Option<JoinHandle<()>>,
+    /// Pauses reading the terminal events.
+    paused: watch::Sender<bool>,
+    /// Acknowledges that the terminal events are no longer read.
+    paused_ack: std_mpsc::Receiver<()>,
 }
 
 impl EventHandler {
@@ -38,46 +45,73 @@ impl EventHandler {
 
         let _tx = tx.clone();
 
+        // the reader is dropped while paused, so that no input is taken from a program using the terminal
+        let (paused, mut paused_rx) = watch::channel(false);
+        let (paused_ack_tx, paused_ack) = std_mpsc::channel();
+
         let task = tokio::spawn(async move {
-            let mut reader = event::EventStream::new();
             let mut tick_interval = tokio::time::interval(tick_delay);
             let mut render_interval = tokio::time::interval(render_delay);
 
             loop {
-                let tick_delay = tick_interval.tick();
-                let render_delay = render_interval.tick();
-                let cs_event = reader.next().fuse();
-                
-                tokio::select! {
-                    maybe_event = cs_event => {
-                        match maybe_event {
-                            Some(Ok(evt)) => {
-                                match evt {
-                                    crossterm::event::Event::Key(key) => {
-                                        if key.kind == crossterm::event::KeyEventKind::Press {
-                                            tx.send(TuiEvent::Key(key)).unwrap();
-                                        }
-                                    },
-                                    _ => {},
-                                }
+                let mut reader = event::EventStream::new();
+
+                loop {
+                    let tick_delay = tick_interval.tick();
+                    let render_delay = render_interval.tick();
+                    let cs_event = reader.next().fuse();
+
+                    tokio::select! {
+                        changed = paused_rx.changed() => {
+                            match changed {
+                                Ok(()) if *paused_rx.borrow_and_update() => break,
+                                Ok(()) => {},
+                                Err(_) => return,
                             }
-                            Some(Err(_)) => {
-                                tx.send(TuiEvent::Error).unwrap();
+                        },
+                        maybe_event = cs_event => {
+                            match maybe_event {
+                                Some(Ok(evt)) => {
+                                    match evt {
+                                        crossterm::event::Event::Key(key) => {
+                                            if key.kind == crossterm::event::KeyEventKind::Press {
+                                                tx.send(TuiEvent::Key(key)).unwrap();
+                                            }
+                                        },
+                                        crossterm::event::Event::Mouse(mouse) => {
+                                            tx.send(TuiEvent::Mouse(mouse)).unwrap();
+                                        },
+                                        _ => {},
+                                    }
+                                }
+                                Some(Err(_)) => {
+                                    tx.send(TuiEvent::Error).unwrap();
+                                }
+                                None => {},
                             }
-                            None => {},
-                        }
-                    },
-                    _ = tick_delay => {
-                        tx.send(TuiEvent::Tick).unwrap();
-                    },
-                    _ = render_delay => {
-                        tx.send(TuiEvent::Render).unwrap();
-                    },
+                        },
+                        _ = tick_delay => {
+                            tx.send(TuiEvent::Tick).unwrap();
+                        },
+                        _ = render_delay => {
+                            tx.send(TuiEvent::Render).unwrap();
+                        },
+                    }
+                }
+
+                drop(reader);
+                let _ = paused_ack_tx.send(());
+
+                // wait to be resumed
+                while *paused_rx.borrow_and_update() {
+                    if paused_rx.changed().await.is_err() {
+                        return;
+                    }
                 }
             }
         });
 
-        Self { _tx, rx, task: Some(task) }
+        Self { _tx, rx, task: Some(task), paused, paused_ack }
     }
 
     /// Receive the next event from the handler thread.
@@ -88,4 +122,18 @@ impl EventHandler {
         let rt = Builder::new_current_thread().enable_all().build().unwrap();
         rt.block_on(self.rx.recv()).ok_or(color_eyre::eyre::eyre!("unable to get event"))
     }
+
+    /// Stops reading the terminal events, e.g. while an external program uses the terminal.
+    ///
+    /// This function blocks until the terminal events are no longer read.
+    pub fn pause(&mut self) {
+        if self.paused.send(true).is_ok() {
+            let _ = self.paused_ack.recv();
+        }
+    }
+
+    /// Resumes reading the terminal events.
+    pub fn resume(&mut self) {
+        let _ = self.paused.send(false);
+    }
 }
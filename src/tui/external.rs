@@ -0,0 +1,75 @@
+use std::{env, fs::{self, OpenOptions}, io::{self, Write}, path::PathBuf, process::Command, time::{SystemTime, UNIX_EPOCH}};
+
+use log::{debug, warn};
+use strum::Display;
+
+// Programs the message can be opened in
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum Program {
+    #[strum(serialize = "editor")]
+    Editor,
+    #[strum(serialize = "pager")]
+    Pager,
+}
+
+impl Program {
+    // Command from the environment along with its arguments, e.g. EDITOR="code --wait"
+    fn command(&self) -> String {
+        let (vars, default): (&[&str], &str) = match self {
+            Program::Editor => (&["VISUAL", "EDITOR"], "vi"),
+            Program::Pager => (&["PAGER"], "less"),
+        };
+
+        vars.iter()
+            .filter_map(|v| env::var(v).ok())
+            .find(|c| !c.trim().is_empty())
+            .unwrap_or(default.to_string())
+    }
+}
+
+// File to be opened in an external program, the TUI is suspended while the program runs
+#[derive(Debug)]
+pub struct ExternalFile {
+    program: Program,
+    path: PathBuf,
+}
+
+impl ExternalFile {
+    // Writes the contents to a new temp file, e.g. /tmp/kafka2i-1760597487571-orders-3-7656.json
+    pub fn new(program: Program, name: &str, contents: &[u8]) -> io::Result<ExternalFile> {
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default();
+        let path = env::temp_dir().join(format!("kafka2i-{}-{}", millis, name));
+
+        // never follows an existing file or symlink at the path
+        let mut file = OpenOptions::new().write(true).create_new(true).open(&path)?;
+        file.write_all(contents)?;
+
+        Ok(ExternalFile { program, path })
+    }
+
+    // Runs the program on the file & waits for it to exit, the file is removed afterwards
+    pub fn open(self) -> Result<(), String> {
+        let command = self.program.command();
+        debug!("opening {} in {} {}", self.path.display(), self.program, command);
+
+        let mut args = command.split_whitespace();
+        let result = match args.next() {
+            Some(program) => Command::new(program)
+                .args(args)
+                .arg(&self.path)
+                .status()
+                .map_err(|err| format!("unable to run {} {}: {}", self.program, program, err))
+                .and_then(|status| match status.success() {
+                    true => Ok(()),
+                    false => Err(format!("{} {} exited with {}", self.program, program, status)),
+                }),
+            None => Err(format!("no {} is set", self.program)),
+        };
+
+        if let Err(err) = fs::remove_file(&self.path) {
+            warn!("unable to remove {}: {}", self.path.display(), err);
+        }
+
+        result
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Local};
+
+const HISTORY_SIZE: usize = 100;
+
+// Location a message was fetched at
+#[derive(Clone, Debug)]
+pub struct Location {
+    // partition in the format topic/partition
+    pub partition: String,
+    pub offset: i64,
+    pub visited_at: DateTime<Local>,
+    // key of the message to recognise the location by
+    pub key: Option<String>,
+}
+
+impl Location {
+    pub fn new(partition: &str, offset: i64, key: Option<String>) -> Location {
+        Location {
+            partition: partition.to_string(),
+            offset,
+            visited_at: Local::now(),
+            key,
+        }
+    }
+
+    fn same_position(&self, other: &Location) -> bool {
+        self.partition == other.partition && self.offset == other.offset
+    }
+}
+
+// Locations visited in a session, navigated back & forward like the history of a browser
+#[derive(Clone, Debug, Default)]
+pub struct NavigationHistory {
+    locations: Vec<Location>,
+    // location currently shown, None until a message is fetched
+    position: Option<usize>,
+}
+
+impl NavigationHistory {
+    // Visit the location, the locations ahead of the current location are dropped
+    // visiting the current location again, e.g. while navigating back, only refreshes it
+    pub fn visit(&mut self, location: Location) {
+        if let Some(current) = self.position.and_then(|p| self.locations.get_mut(p)) && current.same_position(&location) {
+            *current = location;
+            return;
+        }
+
+        let len = self.position.map_or(0, |p| p + 1);
+        self.locations.truncate(len);
+        self.locations.push(location);
+        if self.locations.len() > HISTORY_SIZE {
+            self.locations.remove(0);
+        }
+
+        self.position = Some(self.locations.len() - 1);
+    }
+
+    pub fn back(&mut self) -> Option<&Location> {
+        let position = self.position?.checked_sub(1)?;
+        self.go_to(position)
+    }
+
+    pub fn forward(&mut self) -> Option<&Location> {
+        let position = self.position? + 1;
+        self.go_to(position)
+    }
+
+    // Move to the location at the given index, the locations ahead are kept
+    pub fn go_to(&mut self, idx: usize) -> Option<&Location> {
+        let location = self.locations.get(idx)?;
+        self.position = Some(idx);
+        Some(location)
+    }
+
+    pub fn locations(&self) -> &[Location] {
+        &self.locations
+    }
+
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+}
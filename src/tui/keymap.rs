@@ -0,0 +1,473 @@
+use std::{collections::HashMap, fmt::Display, path::PathBuf, str::FromStr};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use strum::{Display, EnumIter, EnumMessage, EnumString, IntoEnumIterator};
+
+use crate::config::{self, ConfigError, KeymapPreset};
+
+const KEYMAP_FILE: &str = "keymap.toml";
+
+// Actions which can be bound to keys in normal mode
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, EnumIter, EnumString, EnumMessage)]
+#[strum(serialize_all = "snake_case")]
+pub enum Action {
+    #[strum(message = "Navigate between lists")]
+    NextList,
+    #[strum(message = "Navigate between lists in reverse")]
+    PrevList,
+    #[strum(message = "Move up in the selected list")]
+    Up,
+    #[strum(message = "Move down in the selected list")]
+    Down,
+    #[strum(message = "Filter the selected list")]
+    Filter,
+    #[strum(message = "Scroll down the message pane")]
+    ScrollMessageDown,
+    #[strum(message = "Scroll up the message pane")]
+    ScrollMessageUp,
+    #[strum(message = "Move to next offset")]
+    NextOffset,
+    #[strum(message = "Move to previous offset")]
+    PrevOffset,
+    #[strum(message = "Go back to the previously visited message")]
+    Back,
+    #[strum(message = "Go forward to the next visited message")]
+    Forward,
+    #[strum(message = "Show/Hide navigation history")]
+    History,
+    #[strum(message = "Zoom the selected list to fullscreen")]
+    ZoomList,
+    #[strum(message = "Zoom the message pane to fullscreen")]
+    ZoomMessage,
+    #[strum(message = "Grow the lists pane")]
+    GrowLists,
+    #[strum(message = "Shrink the lists pane")]
+    ShrinkLists,
+    #[strum(message = "Switch between auto, horizontal & vertical layout")]
+    ToggleLayout,
+    #[strum(message = "Enter edit mode")]
+    Edit,
+    #[strum(message = "Show/Hide connection info")]
+    ConnectionInfo,
+    #[strum(message = "Show/Hide notification history")]
+    Notifications,
+    #[strum(message = "Show/Hide log pane")]
+    Logs,
+    #[strum(message = "Cycle the minimum level of the log pane")]
+    LogLevel,
+    #[strum(message = "Follow/Pause the latest logs in the log pane")]
+    FollowLogs,
+    #[strum(message = "Copy the message")]
+    CopyMessage,
+    #[strum(message = "Copy the key of the message")]
+    CopyKey,
+    #[strum(message = "Copy the payload of the message")]
+    CopyPayload,
+    #[strum(message = "Copy the headers of the message")]
+    CopyHeaders,
+    #[strum(message = "Copy the message as JSON")]
+    CopyJson,
+    #[strum(message = "Copy a kcat command fetching the message")]
+    CopyKcat,
+    #[strum(message = "Open the message in $EDITOR")]
+    OpenEditor,
+    #[strum(message = "Open the message in $PAGER")]
+    OpenPager,
+    #[strum(message = "Open the raw payload in $PAGER")]
+    OpenPayload,
+    #[strum(message = "Show/Hide internal topics")]
+    ToggleInternalTopics,
+    #[strum(message = "Show/Hide stream processing topics")]
+    ToggleStreamTopics,
+    #[strum(message = "Group topics by their prefixes")]
+    GroupTopics,
+    #[strum(message = "Cycle the order of the selected list")]
+    Sort,
+    #[strum(message = "Expand/Collapse the selected group or open the selected bookmark or location")]
+    Select,
+    #[strum(message = "Bookmark the message")]
+    Bookmark,
+    #[strum(message = "Show/Hide bookmarks")]
+    Bookmarks,
+    #[strum(message = "Delete the selected bookmark")]
+    DeleteBookmark,
+    #[strum(message = "Open a new partition session")]
+    NewSession,
+    #[strum(message = "Close the active partition session")]
+    CloseSession,
+    #[strum(message = "Switch to the next partition session")]
+    NextSession,
+    #[strum(message = "Switch to the next view")]
+    NextView,
+    #[strum(message = "Switch to the previous view")]
+    PrevView,
+    #[strum(message = "Show/Hide help menu")]
+    Help,
+    #[strum(message = "Exit the application")]
+    Quit,
+}
+
+impl Action {
+    pub fn description(&self) -> &'static str {
+        self.get_message().unwrap_or_default()
+    }
+}
+
+// Bindings of the built in keymaps, the Vim keymap overrides a few bindings of the default keymap
+fn preset_bindings(preset: KeymapPreset) -> Vec<(Action, Vec<&'static str>)> {
+    let overrides = match preset {
+        KeymapPreset::Default => vec![],
+        KeymapPreset::Vim => vec![
+            (Action::Up, vec!["k", "up"]),
+            (Action::Down, vec!["j", "down"]),
+            (Action::ScrollMessageDown, vec!["ctrl+d", "J"]),
+            (Action::ScrollMessageUp, vec!["ctrl+u", "K"]),
+            (Action::NextOffset, vec!["l", "right"]),
+            (Action::PrevOffset, vec!["h", "left"]),
+            (Action::Back, vec!["ctrl+o", "alt+left"]),
+            (Action::Help, vec!["?"]),
+            (Action::Quit, vec!["q", "esc"]),
+        ],
+    };
+
+    default_bindings().into_iter()
+        .map(|(action, keys)| match overrides.iter().find(|(a, _)| *a == action) {
+            Some((_, keys)) => (action, keys.clone()),
+            None => (action, keys),
+        })
+        .collect()
+}
+
+fn default_bindings() -> Vec<(Action, Vec<&'static str>)> {
+    vec![
+        (Action::NextList, vec!["tab"]),
+        (Action::PrevList, vec!["backtab"]),
+        (Action::Up, vec!["up"]),
+        (Action::Down, vec!["down"]),
+        (Action::Filter, vec!["/"]),
+        (Action::ScrollMessageDown, vec!["m", "M"]),
+        (Action::ScrollMessageUp, vec!["n", "N"]),
+        (Action::NextOffset, vec!["right"]),
+        (Action::PrevOffset, vec!["left"]),
+        (Action::Back, vec!["alt+left"]),
+        (Action::Forward, vec!["alt+right"]),
+        (Action::History, vec!["H"]),
+        (Action::ZoomList, vec!["z"]),
+        (Action::ZoomMessage, vec!["f"]),
+        (Action::GrowLists, vec![">", "+"]),
+        (Action::ShrinkLists, vec!["<", "-"]),
+        (Action::ToggleLayout, vec!["v"]),
+        (Action::Edit, vec![":"]),
+        (Action::ConnectionInfo, vec!["i"]),
+        (Action::Notifications, vec!["e"]),
+        (Action::Logs, vec!["L"]),
+        (Action::LogLevel, vec!["ctrl+l"]),
+        (Action::FollowLogs, vec!["ctrl+f", "end"]),
+        (Action::CopyMessage, vec!["y"]),
+        (Action::CopyKey, vec!["alt+k"]),
+        (Action::CopyPayload, vec!["alt+p"]),
+        (Action::CopyHeaders, vec!["alt+h"]),
+        (Action::CopyJson, vec!["alt+j"]),
+        (Action::CopyKcat, vec!["alt+c"]),
+        (Action::OpenEditor, vec!["o"]),
+        (Action::OpenPager, vec!["p"]),
+        (Action::OpenPayload, vec!["alt+o"]),
+        (Action::ToggleInternalTopics, vec!["I"]),
+        (Action::ToggleStreamTopics, vec!["S"]),
+        (Action::GroupTopics, vec!["G"]),
+        (Action::Sort, vec!["s"]),
+        (Action::Select, vec!["enter", "space"]),
+        (Action::Bookmark, vec!["b"]),
+        (Action::Bookmarks, vec!["B"]),
+        (Action::DeleteBookmark, vec!["x"]),
+        (Action::NewSession, vec!["ctrl+t"]),
+        (Action::CloseSession, vec!["ctrl+w"]),
+        (Action::NextSession, vec!["w"]),
+        (Action::NextView, vec!["]"]),
+        (Action::PrevView, vec!["["]),
+        (Action::Help, vec!["h"]),
+        (Action::Quit, vec!["esc"]),
+    ]
+}
+
+// A key along with its modifiers, e.g. ctrl+d
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    // shift is already part of the char for char keys & backtab
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> KeyBinding {
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+
+        KeyBinding { code, modifiers }
+    }
+}
+
+impl From<&KeyEvent> for KeyBinding {
+    fn from(key: &KeyEvent) -> Self {
+        KeyBinding::new(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = s;
+
+        // modifiers are prefixes separated by +, e.g. ctrl+alt+d
+        while let Some((modifier, rest)) = key.split_once('+') && !rest.is_empty() {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(ConfigError::new(&format!("invalid modifier {} in key {}", modifier, s))),
+            };
+            key = rest;
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "esc" => KeyCode::Esc,
+            "enter" => KeyCode::Enter,
+            "backspace" => KeyCode::Backspace,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "space" => KeyCode::Char(' '),
+            f if f.len() > 1 && f.starts_with('f') && f[1..].parse::<u8>().is_ok() => KeyCode::F(f[1..].parse().unwrap_or_default()),
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(ConfigError::new(&format!("invalid key {}", s))),
+                }
+            }
+        };
+
+        Ok(KeyBinding::new(code, modifiers))
+    }
+}
+
+impl Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut key = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            key.push_str("CTRL+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            key.push_str("ALT+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            key.push_str("SHIFT+");
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => key.push_str("SPACE"),
+            KeyCode::Char(c) => key.push(c),
+            KeyCode::BackTab => key.push_str("SHIFT+TAB"),
+            KeyCode::PageUp => key.push_str("PAGEUP"),
+            KeyCode::PageDown => key.push_str("PAGEDOWN"),
+            code => key.push_str(&code.to_string().to_uppercase()),
+        }
+
+        write!(f, "{}", key)
+    }
+}
+
+// Keymap file, bindings override the bindings of the preset
+#[derive(Debug, Default, Deserialize)]
+struct KeymapFile {
+    preset: Option<KeymapPreset>,
+    #[serde(default)]
+    bindings: HashMap<String, Vec<String>>,
+}
+
+// Keymap registry resolving key events to actions
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyBinding>)>,
+    actions: HashMap<KeyBinding, Action>,
+}
+
+impl Keymap {
+    // Load keymap from the given file or from the default location if it exists
+    // preset passed as an argument takes precedence over the preset in the file
+    pub fn load(file: Option<&str>, preset: Option<KeymapPreset>) -> Result<Keymap, ConfigError> {
+        let location = match file {
+            Some(f) => Some(PathBuf::from(f)),
+            None => config::config_dir()
+                .map(|d| d.join(KEYMAP_FILE))
+                .filter(|f| f.exists()),
+        };
+
+        let keymap_file = match location {
+            Some(location) => {
+                log::info!("loading keymap from {}", location.display());
+                let content = std::fs::read_to_string(&location)
+                    .map_err(|err| ConfigError::new(&format!("unable to read keymap {}: {}", location.display(), err)))?;
+                toml::from_str::<KeymapFile>(&content)
+                    .map_err(|err| ConfigError::new(&format!("unable to parse keymap {}: {}", location.display(), err)))?
+            },
+            None => KeymapFile::default(),
+        };
+
+        let preset = preset.or(keymap_file.preset).unwrap_or_default();
+        let mut bindings = preset_bindings(preset).into_iter()
+            .map(|(action, keys)| Ok((action, keys.iter().map(|k| k.parse()).collect::<Result<Vec<KeyBinding>, ConfigError>>()?)))
+            .collect::<Result<HashMap<Action, Vec<KeyBinding>>, ConfigError>>()?;
+
+        // override preset bindings with the ones from the file
+        for (action_name, keys) in keymap_file.bindings {
+            let action = Action::from_str(&action_name)
+                .map_err(|_| ConfigError::new(&format!("invalid action {} in keymap", action_name)))?;
+            let keys = keys.iter()
+                .map(|k| k.parse())
+                .collect::<Result<Vec<KeyBinding>, ConfigError>>()?;
+            bindings.insert(action, keys);
+        }
+
+        Keymap::new(bindings)
+    }
+
+    fn new(mut bindings: HashMap<Action, Vec<KeyBinding>>) -> Result<Keymap, ConfigError> {
+        let bindings = Action::iter()
+            .map(|action| (action, bindings.remove(&action).unwrap_or_default()))
+            .collect::<Vec<(Action, Vec<KeyBinding>)>>();
+
+        // a key can only be bound to a single action
+        let mut actions = HashMap::new();
+        for (action, keys) in &bindings {
+            for key in keys {
+                if let Some(existing) = actions.insert(*key, *action) {
+                    return Err(ConfigError::new(&format!("key {} is bound to both {} and {}", key, existing, action)));
+                }
+            }
+        }
+
+        Ok(Keymap { bindings, actions })
+    }
+
+    // Action bound to the key event, if any
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        self.actions.get(&key.into()).copied()
+    }
+
+    // Keys bound to the action, e.g. "m/M"
+    pub fn keys(&self, action: Action) -> String {
+        self.bindings.iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.iter().map(|k| k.to_string()).collect::<Vec<String>>().join("/"))
+            .unwrap_or_default()
+    }
+
+    // All the actions along with the keys bound to them, in the order of the actions
+    pub fn bindings(&self) -> impl Iterator<Item = (Action, String)> + '_ {
+        self.bindings.iter().map(|(action, _)| (*action, self.keys(*action)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyBinding {
+        KeyBinding::from(&KeyEvent::new(code, modifiers))
+    }
+
+    fn preset_keymap(preset: KeymapPreset) -> Result<Keymap, ConfigError> {
+        let bindings = preset_bindings(preset).into_iter()
+            .map(|(action, keys)| (action, keys.iter().map(|k| k.parse().unwrap()).collect()))
+            .collect();
+
+        Keymap::new(bindings)
+    }
+
+    #[test]
+    fn parse_keys() {
+        assert_eq!("d".parse::<KeyBinding>().unwrap(), key(KeyCode::Char('d'), KeyModifiers::NONE));
+        assert_eq!("enter".parse::<KeyBinding>().unwrap(), key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!("space".parse::<KeyBinding>().unwrap(), key(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert_eq!("PageDown".parse::<KeyBinding>().unwrap(), key(KeyCode::PageDown, KeyModifiers::NONE));
+        assert_eq!("F12".parse::<KeyBinding>().unwrap(), key(KeyCode::F(12), KeyModifiers::NONE));
+        // a single f is a char rather than a function key
+        assert_eq!("f".parse::<KeyBinding>().unwrap(), key(KeyCode::Char('f'), KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn parse_keys_with_modifiers() {
+        assert_eq!("ctrl+d".parse::<KeyBinding>().unwrap(), key(KeyCode::Char('d'), KeyModifiers::CONTROL));
+        assert_eq!("CTRL+Alt+left".parse::<KeyBinding>().unwrap(), key(KeyCode::Left, KeyModifiers::CONTROL | KeyModifiers::ALT));
+        assert_eq!("+".parse::<KeyBinding>().unwrap(), key(KeyCode::Char('+'), KeyModifiers::NONE));
+        assert_eq!("ctrl++".parse::<KeyBinding>().unwrap(), key(KeyCode::Char('+'), KeyModifiers::CONTROL));
+    }
+
+    #[test]
+    fn parse_keys_with_shift() {
+        // shift is part of the char, e.g. SHIFT+h is received as H
+        assert_eq!("H".parse::<KeyBinding>().unwrap(), key(KeyCode::Char('H'), KeyModifiers::SHIFT));
+        assert_eq!("shift+tab".parse::<KeyBinding>().unwrap(), key(KeyCode::BackTab, KeyModifiers::SHIFT));
+        assert_eq!("backtab".parse::<KeyBinding>().unwrap(), key(KeyCode::BackTab, KeyModifiers::SHIFT));
+        assert_eq!("shift+up".parse::<KeyBinding>().unwrap(), key(KeyCode::Up, KeyModifiers::SHIFT));
+    }
+
+    #[test]
+    fn parse_malformed_keys() {
+        for k in ["", "ab", "ctrl+", "meta+d", "ctrl+ab", "f256", "ctrl+meta+d"] {
+            assert!(k.parse::<KeyBinding>().is_err(), "{} should be invalid", k);
+        }
+    }
+
+    #[test]
+    fn display_keys() {
+        assert_eq!("ctrl+d".parse::<KeyBinding>().unwrap().to_string(), "CTRL+d");
+        assert_eq!("space".parse::<KeyBinding>().unwrap().to_string(), "SPACE");
+        assert_eq!("shift+tab".parse::<KeyBinding>().unwrap().to_string(), "SHIFT+TAB");
+    }
+
+    #[test]
+    fn presets_bind_a_key_to_a_single_action() {
+        assert!(preset_keymap(KeymapPreset::Default).is_ok());
+        assert!(preset_keymap(KeymapPreset::Vim).is_ok());
+    }
+
+    #[test]
+    fn presets_bind_every_action() {
+        for preset in [KeymapPreset::Default, KeymapPreset::Vim] {
+            let keymap = preset_keymap(preset).unwrap();
+            for action in Action::iter() {
+                assert!(!keymap.keys(action).is_empty(), "{} is not bound in the {} keymap", action, preset);
+            }
+        }
+    }
+
+    #[test]
+    fn vim_preset_overrides_the_default_bindings() {
+        let (default, vim) = (preset_keymap(KeymapPreset::Default).unwrap(), preset_keymap(KeymapPreset::Vim).unwrap());
+        assert_eq!(vim.keys(Action::Down), "j/DOWN");
+        assert_eq!(vim.keys(Action::Quit), "q/ESC");
+        assert_eq!(vim.keys(Action::Bookmark), default.keys(Action::Bookmark));
+    }
+
+    #[test]
+    fn keys_bound_to_several_actions() {
+        let d: KeyBinding = "d".parse().unwrap();
+        let bindings = HashMap::from([(Action::Up, vec![d]), (Action::Down, vec![d])]);
+        assert!(Keymap::new(bindings).is_err());
+    }
+}
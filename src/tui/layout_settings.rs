@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use crate::config;
+
+const LAYOUT_FILE: &str = "layout.toml";
+
+const DEFAULT_LISTS_SIZE: u16 = 25;
+const MIN_LISTS_SIZE: u16 = 10;
+const MAX_LISTS_SIZE: u16 = 80;
+const LISTS_SIZE_STEP: u16 = 5;
+
+// Terminals narrower than this switch to the vertical layout in auto mode
+const NARROW_WIDTH: u16 = 100;
+
+// Orientation of the lists and the details
+#[derive(Debug, Display, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+    // vertical on narrow terminals, horizontal otherwise
+    #[default]
+    #[strum(serialize = "auto")]
+    Auto,
+    // lists on the left of the details
+    #[strum(serialize = "horizontal")]
+    Horizontal,
+    // lists above the details
+    #[strum(serialize = "vertical")]
+    Vertical,
+}
+
+// Order of the topics list
+#[derive(Debug, Display, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum TopicSort {
+    #[default]
+    Name,
+    Partitions,
+    // messages retained
+    Messages,
+    // messages produced since the previous refresh
+    Activity,
+}
+
+impl TopicSort {
+    fn next(&self) -> TopicSort {
+        match self {
+            TopicSort::Name => TopicSort::Partitions,
+            TopicSort::Partitions => TopicSort::Messages,
+            TopicSort::Messages => TopicSort::Activity,
+            TopicSort::Activity => TopicSort::Name,
+        }
+    }
+}
+
+// Order of the consumer groups list
+#[derive(Debug, Display, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum GroupSort {
+    #[default]
+    Name,
+    State,
+    Members,
+    Lag,
+}
+
+impl GroupSort {
+    fn next(&self) -> GroupSort {
+        match self {
+            GroupSort::Name => GroupSort::State,
+            GroupSort::State => GroupSort::Members,
+            GroupSort::Members => GroupSort::Lag,
+            GroupSort::Lag => GroupSort::Name,
+        }
+    }
+}
+
+impl Orientation {
+    fn next(&self) -> Orientation {
+        match self {
+            Orientation::Auto => Orientation::Horizontal,
+            Orientation::Horizontal => Orientation::Vertical,
+            Orientation::Vertical => Orientation::Auto,
+        }
+    }
+}
+
+// Layout chosen by the user, persisted between sessions
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutSettings {
+    pub orientation: Orientation,
+    // percentage of the width (or height in vertical layout) taken by the lists
+    pub lists_size: u16,
+    // internal & stream processing topics hidden from the topics list
+    pub hide_internal_topics: bool,
+    pub hide_stream_topics: bool,
+    // topics folded into groups by their prefixes
+    pub group_topics: bool,
+    pub topic_sort: TopicSort,
+    pub group_sort: GroupSort,
+}
+
+impl Default for LayoutSettings {
+    fn default() -> Self {
+        LayoutSettings {
+            orientation: Orientation::default(),
+            lists_size: DEFAULT_LISTS_SIZE,
+            hide_internal_topics: false,
+            hide_stream_topics: false,
+            group_topics: false,
+            topic_sort: TopicSort::default(),
+            group_sort: GroupSort::default(),
+        }
+    }
+}
+
+impl LayoutSettings {
+    // Load the layout persisted in the previous session
+    pub fn load() -> LayoutSettings {
+        let settings: LayoutSettings = config::load_state(LAYOUT_FILE);
+        LayoutSettings {
+            lists_size: settings.lists_size.clamp(MIN_LISTS_SIZE, MAX_LISTS_SIZE),
+            ..settings
+        }
+    }
+
+    // Persist the layout for the next session
+    pub fn save(&self) {
+        config::save_state(LAYOUT_FILE, self);
+    }
+
+    pub fn grow_lists(&mut self) {
+        self.lists_size = (self.lists_size + LISTS_SIZE_STEP).min(MAX_LISTS_SIZE);
+    }
+
+    pub fn shrink_lists(&mut self) {
+        self.lists_size = self.lists_size.saturating_sub(LISTS_SIZE_STEP).max(MIN_LISTS_SIZE);
+    }
+
+    pub fn toggle_orientation(&mut self) {
+        self.orientation = self.orientation.next();
+    }
+
+    pub fn toggle_internal_topics(&mut self) {
+        self.hide_internal_topics = !self.hide_internal_topics;
+    }
+
+    pub fn toggle_stream_topics(&mut self) {
+        self.hide_stream_topics = !self.hide_stream_topics;
+    }
+
+    pub fn toggle_topic_groups(&mut self) {
+        self.group_topics = !self.group_topics;
+    }
+
+    pub fn cycle_topic_sort(&mut self) {
+        self.topic_sort = self.topic_sort.next();
+    }
+
+    pub fn cycle_group_sort(&mut self) {
+        self.group_sort = self.group_sort.next();
+    }
+
+    // Whether the vertical layout is to be used for the given width
+    pub fn is_vertical(&self, width: u16) -> bool {
+        match self.orientation {
+            Orientation::Auto => width < NARROW_WIDTH,
+            Orientation::Horizontal => false,
+            Orientation::Vertical => true,
+        }
+    }
+}
@@ -1,4 +1,15 @@
 pub mod app;
 pub mod events;
 pub mod widgets;
-pub mod single_layout;
\ No newline at end of file
+pub mod single_layout;
+pub mod keymap;
+pub mod theme;
+pub mod layout_settings;
+pub mod command;
+pub mod notification;
+pub mod views;
+pub mod clipboard;
+pub mod external;
+pub mod saved_state;
+pub mod bookmarks;
+pub mod history;
@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use strum::Display;
+
+// Level of the notification, also decides how long it is displayed for
+#[derive(Clone, Copy, Debug, Display, PartialEq)]
+pub enum Level {
+    #[strum(serialize = "INFO")]
+    Info,
+    #[strum(serialize = "WARN")]
+    Warn,
+    #[strum(serialize = "ERROR")]
+    Error,
+}
+
+impl Level {
+    fn timeout(&self) -> Duration {
+        match self {
+            Level::Info => Duration::from_secs(5),
+            Level::Warn => Duration::from_secs(10),
+            Level::Error => Duration::from_secs(15),
+        }
+    }
+}
+
+// Notification displayed in the status bar and kept in the notification history
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub level: Level,
+    pub message: String,
+    pub timestamp: DateTime<Local>,
+    created: Instant,
+}
+
+impl Notification {
+    pub fn new(level: Level, message: String) -> Notification {
+        Notification {
+            level,
+            message,
+            timestamp: Local::now(),
+            created: Instant::now(),
+        }
+    }
+
+    // Whether the notification is to be dismissed from the status bar
+    pub fn expired(&self) -> bool {
+        self.created.elapsed() >= self.level.timeout()
+    }
+
+    pub fn time(&self) -> String {
+        self.timestamp.format("%H:%M:%S").to_string()
+    }
+}
+
+// Log the notification at the respective level, the full message is always logged
+pub fn log(notification: &Notification) {
+    match notification.level {
+        Level::Info => log::info!("{}", notification.message),
+        Level::Warn => log::warn!("{}", notification.message),
+        Level::Error => log::error!("{}", notification.message),
+    }
+}
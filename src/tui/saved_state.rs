@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, Config};
+
+use super::command::{self, CommandError};
+
+const STATE_FILE: &str = "state.toml";
+
+// Position the app was left at, persisted between sessions
+// also used as the position to open the app at when it is given as args
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedState {
+    // bootstrap servers of the cluster
+    pub cluster: String,
+    // name of the list in focus
+    pub list: Option<String>,
+    pub topic: Option<String>,
+    pub partition: Option<i32>,
+    pub offset: Option<i64>,
+    // epoch in milliseconds, only given as an arg & never persisted
+    #[serde(skip)]
+    pub timestamp: Option<i64>,
+}
+
+impl SavedState {
+    // Load the state of the previous session
+    pub fn load() -> SavedState {
+        config::load_state(STATE_FILE)
+    }
+
+    // Persist the state for the next session
+    pub fn save(&self) {
+        config::save_state(STATE_FILE, self);
+    }
+
+    // Position to open the app at, the position given as args replaces the position of the previous session
+    // the previous position is restored only on the same cluster as the topics are not shared across clusters
+    pub fn start_position(self, config: &Config) -> Result<Option<SavedState>, CommandError> {
+        let Some(topic) = config.topic.clone() else {
+            return Ok((self.cluster == config.bootstrap_servers && self.topic.is_some()).then_some(self));
+        };
+
+        let timestamp = match &config.timestamp {
+            Some(timestamp) => Some(command::parse_timestamp(timestamp)?),
+            None => None,
+        };
+
+        Ok(Some(SavedState {
+            cluster: config.bootstrap_servers.clone(),
+            list: None,
+            topic: Some(topic),
+            partition: config.partition,
+            offset: config.offset,
+            timestamp,
+        }))
+    }
+}
@@ -1,54 +1,171 @@
-use ratatui::{layout::{Constraint, Layout, Rect}, style::Stylize, text::{Line, Span, Text}, widgets::{Clear, ScrollbarOrientation}, Frame};
-use crate::kafka::metadata::Metadata;
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
 
-use super::widgets::{AppWidget, Direction, InputEvent, UIInput, UIList, UIParagraph, UIParagraphWithScrollbar, UITable};
+use log::LevelFilter;
+use strum::IntoEnumIterator;
+use ratatui::{layout::{Constraint, Layout, Position, Rect}, style::Stylize, text::{Line, Span, Text}, widgets::{Clear, ScrollbarOrientation}, Frame};
+use crate::{certs::ConnectionInfo, kafka::{activity::Activity, metadata::Metadata}, logger::{LogBuffer, LogRecord}};
+
+use super::bookmarks::Bookmark;
+use super::command::CommandName;
+use super::history::NavigationHistory;
+use super::keymap::{Action, Keymap};
+use super::layout_settings::{GroupSort, LayoutSettings, TopicSort};
+use super::notification::{Level, Notification};
+use super::theme::Theme;
+use super::views::{AdminLayout, GroupsLayout, ProducerLayout, StatsLayout, View, VIEW_TITLES};
+
+use super::widgets::{AppWidget, Direction, InputEvent, UIInput, UIList, UIParagraph, UIParagraphWithScrollbar, UITable, UITabs};
 
 const APP_NAME: &str = "Kafka2i - TUI for Kafka";
 const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
-const APP_FOOTER: &str = "Press <H> for help | Press <ESC> to quit";
 
 pub const BROKERS_LIST: &str = "Brokers";
 pub const CONSUMER_GROUPS_LIST: &str = "Consumer Groups";
 pub const TOPICS_LIST: &str = "Topics";
 pub const PARTITIONS_LIST: &str = "Partitions";
+const BOOKMARKS_LIST: &str = "Bookmarks";
+const HISTORY_LIST: &str = "History";
+
+// Keys longer than this are cut short in the history
+const KEY_PREVIEW_LENGTH: usize = 32;
+
+// Topics are grouped by the prefixes up to these, e.g. team.domain.event
+const TOPIC_SEPARATORS: [char; 3] = ['.', '-', '_'];
+
+const NOTIFICATION_HISTORY_SIZE: usize = 100;
 
 
 
 // Top level application layout
 pub struct AppLayout<'a> {
     pub header_layout: HeaderLayout<'a>,
+    pub tabs: UITabs<'a>,
+    pub view: View,
     pub main_layout: MainLayout<'a>,
+    pub producer_layout: ProducerLayout<'a>,
+    pub admin_layout: AdminLayout<'a>,
+    pub groups_layout: GroupsLayout<'a>,
+    pub stats_layout: StatsLayout<'a>,
+    // metadata & activity the views were last refreshed with
+    views_metadata: Option<(Metadata, Activity)>,
     pub footer_layout: FooterLayout<'a>,
     pub help_layout: HelpLayout<'a>,
     pub show_help: bool,
+    pub connection_info_layout: ConnectionInfoLayout<'a>,
+    pub show_connection_info: bool,
+    pub notifications_layout: NotificationsLayout<'a>,
+    pub show_notifications: bool,
+    pub bookmarks_layout: BookmarksLayout<'a>,
+    pub show_bookmarks: bool,
+    pub history_layout: HistoryLayout<'a>,
+    pub show_history: bool,
+    pub logs_layout: LogsLayout<'a>,
+    pub show_logs: bool,
 }
 
 impl <'a> AppLayout<'a> {
-    pub fn new(metadata: &Metadata) -> AppLayout<'a> {
-        AppLayout{
-            header_layout: HeaderLayout::new(),
-            main_layout: MainLayout::new(metadata),
-            footer_layout: FooterLayout::new(),
-            help_layout: HelpLayout::new(),
+    pub fn new(metadata: &Metadata, connection_info: &ConnectionInfo, keymap: &Keymap, theme: Theme, settings: LayoutSettings, log_buffer: LogBuffer, read_only: bool) -> AppLayout<'a> {
+        let mut layout = AppLayout{
+            header_layout: HeaderLayout::new(theme),
+            tabs: UITabs::new("", &VIEW_TITLES, theme),
+            view: View::default(),
+            main_layout: MainLayout::new(metadata, theme, settings),
+            producer_layout: ProducerLayout::new(theme, read_only),
+            admin_layout: AdminLayout::new(theme),
+            groups_layout: GroupsLayout::new(theme),
+            stats_layout: StatsLayout::new(theme),
+            views_metadata: None,
+            footer_layout: FooterLayout::new(keymap, theme),
+            help_layout: HelpLayout::new(keymap, theme),
             show_help: false,
+            connection_info_layout: ConnectionInfoLayout::new(connection_info, theme),
+            show_connection_info: false,
+            notifications_layout: NotificationsLayout::new(theme),
+            show_notifications: false,
+            bookmarks_layout: BookmarksLayout::new(theme),
+            show_bookmarks: false,
+            history_layout: HistoryLayout::new(theme),
+            show_history: false,
+            logs_layout: LogsLayout::new(log_buffer, theme),
+            show_logs: false,
+        };
+
+        layout.refresh_views(metadata, &Activity::default());
+        layout
+    }
+
+    // Popups shown over the lists & the message pane, the log pane replaces them instead
+    pub fn popup_shown(&self) -> bool {
+        self.show_help || self.show_connection_info || self.show_notifications || self.show_bookmarks || self.show_history
+    }
+
+    pub fn select_view(&mut self, view: View) {
+        self.view = view;
+        self.tabs.select(view.idx());
+    }
+
+    // refresh the views other than the consumer view with the latest metadata & activity
+    // views are only refreshed when these change as refreshing resets their scroll position
+    pub fn refresh_views(&mut self, metadata: &Metadata, activity: &Activity) {
+        if self.views_metadata.as_ref().is_some_and(|(m, a)| m == metadata && a == activity) {
+            return;
         }
+
+        self.admin_layout.refresh(metadata);
+        self.groups_layout.refresh(metadata, activity);
+        self.stats_layout.refresh(metadata, activity);
+        self.views_metadata = Some((metadata.clone(), activity.clone()));
     }
 
     pub fn render(&mut self, frame: &mut Frame) {
         use Constraint::*;
 
         //overall layout
-        let outer_layout = Layout::vertical([Length(5), Fill(1), Length(3)]);
-        let [title, main, footer] = outer_layout.areas(frame.size());
+        let outer_layout = Layout::vertical([Length(5), Length(2), Fill(1), Length(3)]);
+        let [title, tabs, main, footer] = outer_layout.areas(frame.size());
 
         self.header_layout.render(frame, title);
-        self.main_layout.render(frame, main);
+        self.tabs.render(frame, tabs);
         self.footer_layout.render(frame, footer);
 
+        // log pane takes the place of the selected view
+        if self.show_logs {
+            self.logs_layout.render(frame, main);
+        } else {
+            match self.view {
+                View::Consumer => self.main_layout.render(frame, main),
+                View::Producer => self.producer_layout.render(frame, main),
+                View::Admin => self.admin_layout.render(frame, main),
+                View::Groups => self.groups_layout.render(frame, main),
+                View::Stats => self.stats_layout.render(frame, main),
+            }
+        }
+
         // centered help layout
         if self.show_help {
             self.help_layout.render(frame, self.centered_help_area(frame));
         }
+
+        // centered connection info layout
+        if self.show_connection_info {
+            self.connection_info_layout.render(frame, self.centered_help_area(frame));
+        }
+
+        // centered notification history layout
+        if self.show_notifications {
+            self.notifications_layout.render(frame, self.centered_help_area(frame));
+        }
+
+        // centered bookmarks layout
+        if self.show_bookmarks {
+            self.bookmarks_layout.render(frame, self.centered_help_area(frame));
+        }
+
+        // centered navigation history layout
+        if self.show_history {
+            self.history_layout.render(frame, self.centered_help_area(frame));
+        }
     }
 
     // function to get a rect of 60 x 40 in the center of the terminal
@@ -92,15 +209,15 @@ pub struct HeaderLayout<'a> {
 }
 
 impl <'a> HeaderLayout<'a> {
-    pub fn new() -> HeaderLayout<'a> {
+    pub fn new(theme: Theme) -> HeaderLayout<'a> {
         let crab = emojis::get_by_shortcode("crab").unwrap();
         let heart = emojis::get_by_shortcode("heart").unwrap();
 
         HeaderLayout{
             title: UIParagraph::new("".to_string(), Text::from(vec![
-                Span::from(format!("{} (v{})", APP_NAME, APP_VERSION)).bold().green().into_centered_line(),
-                Span::from(format!("Built in {} with {}", crab.as_str(), heart.as_str())).bold().gray().into_centered_line()
-            ]))
+                Span::from(format!("{} (v{})", APP_NAME, APP_VERSION)).bold().fg(theme.title).into_centered_line(),
+                Span::from(format!("Built in {} with {}", crab.as_str(), heart.as_str())).bold().fg(theme.text).into_centered_line()
+            ]), theme)
         }
     }
 
@@ -109,27 +226,83 @@ impl <'a> HeaderLayout<'a> {
     }
 }
 
+// Pane zoomed to fill the main layout
+#[derive(Clone, Copy, PartialEq)]
+pub enum Zoom {
+    None,
+    List,
+    Message,
+}
+
 // Main layout
 pub struct MainLayout<'a> {
     pub lists_layout: ListsLayout<'a>,
-    pub details_layout: DetailsLayout<'a>
+    pub details_layout: DetailsLayout<'a>,
+    pub settings: LayoutSettings,
+    zoom: Zoom,
 }
 
 impl <'a> MainLayout<'a> {
-    pub fn new(metadata: &Metadata) -> MainLayout<'a> {
+    pub fn new(metadata: &Metadata, theme: Theme, settings: LayoutSettings) -> MainLayout<'a> {
         MainLayout {
-            lists_layout: ListsLayout::new(metadata),
-            details_layout: DetailsLayout::new()
+            lists_layout: ListsLayout::new(metadata, theme, &settings),
+            details_layout: DetailsLayout::new(theme),
+            settings,
+            zoom: Zoom::None,
         }
     }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
         use Constraint::*;
-        let horizontal_layout = Layout::horizontal([Percentage(25), Fill(1)]);
-        let [list_layout, details_layout] = horizontal_layout.areas(area);
 
-        self.lists_layout.render(frame, list_layout);
-        self.details_layout.render(frame, details_layout);
+        match self.zoom {
+            Zoom::List => self.lists_layout.render_selected(frame, area),
+            Zoom::Message => self.details_layout.render_message(frame, area),
+            Zoom::None => {
+                // lists are stacked above the details on narrow terminals
+                let vertical = self.settings.is_vertical(area.width);
+                let constraints = [Percentage(self.settings.lists_size), Fill(1)];
+                let [list_layout, details_layout] = if vertical {
+                    Layout::vertical(constraints).areas(area)
+                } else {
+                    Layout::horizontal(constraints).areas(area)
+                };
+
+                self.lists_layout.render(frame, list_layout, vertical);
+                self.details_layout.render(frame, details_layout);
+            },
+        }
+    }
+
+    // refresh lists with the latest metadata, activity & the list settings
+    pub fn refresh_lists(&mut self, metadata: &Metadata, activity: &Activity) {
+        self.lists_layout.refresh(metadata, activity, &self.settings);
+    }
+
+    // zoom the given pane, zooming the zoomed pane again restores the layout
+    pub fn toggle_zoom(&mut self, zoom: Zoom) {
+        self.zoom = if self.zoom == zoom { Zoom::None } else { zoom };
+    }
+
+    // index of the visible list at the given position
+    pub fn list_at(&self, position: Position) -> Option<usize> {
+        match self.zoom {
+            Zoom::None => self.lists_layout.list_at(position),
+            Zoom::List => self.lists_layout.selected_list().contains(position).then_some(self.lists_layout.selected_idx()),
+            Zoom::Message => None,
+        }
+    }
+
+    pub fn message_visible(&self) -> bool {
+        self.zoom != Zoom::List
+    }
+
+    // index of the visible session pane at the given position
+    pub fn session_at(&self, position: Position) -> Option<usize> {
+        match self.zoom {
+            Zoom::None => self.details_layout.session_at(position),
+            _ => None,
+        }
     }
 }
 
@@ -140,34 +313,46 @@ pub struct ListsLayout<'a> {
 }
 
 impl <'a> ListsLayout<'a> {
-    pub fn new(metadata: &Metadata) -> ListsLayout<'a> {
+    pub fn new(metadata: &Metadata, theme: Theme, settings: &LayoutSettings) -> ListsLayout<'a> {
         // initlaise all UI Lists
         let mut lists = vec![];
-        lists.push(UIList::new(BROKERS_LIST.to_string(), metadata.brokers_list()));
-        lists.push(UIList::new(CONSUMER_GROUPS_LIST.to_string(), metadata.consumer_group_lists()));
-        lists.push(UIList::new(TOPICS_LIST.to_string(), metadata.topics_list()));
-        lists.push(UIList::new(PARTITIONS_LIST.to_string(), vec![]));
+        lists.push(UIList::new(BROKERS_LIST.to_string(), metadata.brokers_list(), theme));
+        lists.push(UIList::new(CONSUMER_GROUPS_LIST.to_string(), vec![], theme));
+        lists.push(UIList::new(TOPICS_LIST.to_string(), vec![], theme));
+        lists.push(UIList::new(PARTITIONS_LIST.to_string(), vec![], theme));
 
         // select and highlight first list
         let selected_list = 0; 
         lists[selected_list].highlight_border();
 
-        ListsLayout {
+        // groups & topics are ordered & displayed as per the settings, activity is fetched later in the background
+        let mut layout = ListsLayout {
             selected_list,
             lists
-        }
+        };
+        layout.refresh(metadata, &Activity::default(), settings);
+        layout
     }
 
-    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+    // lists are placed side by side when the lists are stacked above the details
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, vertical: bool) {
         use Constraint::*;
-        let vertical_layout = Layout::vertical([Percentage(10), Percentage(30), Percentage(30), Fill(1)]);
-        let list_areas: [Rect; 4] = vertical_layout.areas(area);
+        let list_areas: [Rect; 4] = if vertical {
+            Layout::horizontal([Percentage(16), Percentage(28), Percentage(28), Fill(1)]).areas(area)
+        } else {
+            Layout::vertical([Percentage(10), Percentage(30), Percentage(30), Fill(1)]).areas(area)
+        };
 
         for i in 0..self.lists.len() {
             self.lists[i].render(frame, list_areas[i]);
         }
     }
 
+    // render only the selected list in the whole area
+    pub fn render_selected(&mut self, frame: &mut Frame, area: Rect) {
+        self.lists[self.selected_list].render(frame, area);
+    }
+
     pub fn get_list_by_name(&mut self, name: &str) -> Option<&mut UIList<'a>> {
         self.lists.iter_mut()
             .filter(|l| l.name().starts_with(name))
@@ -178,10 +363,49 @@ impl <'a> ListsLayout<'a> {
         self.lists[self.selected_list].handle_navigation(direction);
     }
 
-    pub fn handle_tab(&mut self, back_tab: bool) {
-        // normalise current block
-        self.lists[self.selected_list].normalise_border();
+    // refresh lists with the latest metadata & activity, only the lists with changes are updated
+    pub fn refresh(&mut self, metadata: &Metadata, activity: &Activity, settings: &LayoutSettings) {
+        if let Some(topics) = self.get_list_by_name(TOPICS_LIST) && topics.is_grouped() != settings.group_topics {
+            topics.set_grouping(settings.group_topics.then_some(&TOPIC_SEPARATORS));
+        }
+
+        let topic_partitions = self.get_list_by_name(TOPICS_LIST)
+            .and_then(|l| l.selected_item())
+            .and_then(|t| metadata.get_topic(&t))
+            .map(|t| t.partition_names())
+            .unwrap_or_default();
+
+        let (groups, group_details) = groups_list(metadata, activity, settings.group_sort);
+        let (topics, topic_details) = topics_list(metadata, activity, settings);
+        let group_sort = (settings.group_sort != GroupSort::Name).then(|| settings.group_sort.to_string());
+        let topic_sort = (settings.topic_sort != TopicSort::Name).then(|| settings.topic_sort.to_string());
 
+        let refreshed_lists = [
+            (BROKERS_LIST, metadata.brokers_list(), None),
+            (CONSUMER_GROUPS_LIST, groups, Some((group_details, group_sort))),
+            (TOPICS_LIST, topics, Some((topic_details, topic_sort))),
+            (PARTITIONS_LIST, topic_partitions, None),
+        ];
+
+        for (name, items, details) in refreshed_lists {
+            let Some(list) = self.get_list_by_name(name) else {
+                continue;
+            };
+
+            if list.items() != items.as_slice() {
+                list.update(items);
+            }
+
+            if let Some((details, sort)) = details {
+                if *list.details() != details {
+                    list.set_details(details);
+                }
+                list.set_sort(sort);
+            }
+        }
+    }
+
+    pub fn handle_tab(&mut self, back_tab: bool) {
         let mut new_idx = self.selected_list;
 
         if back_tab {
@@ -198,40 +422,184 @@ impl <'a> ListsLayout<'a> {
             }
         }
         
+        self.focus(new_idx);
+    }
+
+    // focus the list at the given index
+    pub fn focus(&mut self, idx: usize) {
+        // normalise current block
+        self.lists[self.selected_list].normalise_border();
+
         // higlight selected list border
-        self.selected_list = new_idx;
+        self.selected_list = idx;
         self.lists[self.selected_list].highlight_border();
     }
 
+    // index of the list at the given position
+    pub fn list_at(&self, position: Position) -> Option<usize> {
+        self.lists.iter().position(|l| l.contains(position))
+    }
+
+    pub fn selected_idx(&self) -> usize {
+        self.selected_list
+    }
+
     pub fn selected_list(&self) -> &UIList<'a> {
         &self.lists[self.selected_list]
     }
+
+    pub fn selected_list_mut(&mut self) -> &mut UIList<'a> {
+        &mut self.lists[self.selected_list]
+    }
 }
 
 // Details Layout
 pub struct DetailsLayout<'a> {
     pub details: UITable<'a>,
-    pub message: UIParagraphWithScrollbar<'a>
+    // message pane of each session, displayed side by side
+    messages: Vec<UIParagraphWithScrollbar<'a>>,
+    active: usize,
+    theme: Theme,
 }
 
 impl <'a> DetailsLayout<'a> {
-    pub fn new() -> DetailsLayout<'a> {
+    pub fn new(theme: Theme) -> DetailsLayout<'a> {
         let column_headers = vec!["Broker", "Consumer Group", "Topic", "Partition"];
         let column_constraints: Vec<u16> = vec![25, 25, 25, 25];
         let data = vec![vec!["".to_string(); column_constraints.len()]];
 
         DetailsLayout {
-            details: UITable::new(column_headers, column_constraints, data),
-            message: UIParagraphWithScrollbar::new("Message".to_string(), "".into(), ScrollbarOrientation::VerticalRight),
+            details: UITable::new(column_headers, column_constraints, data, theme),
+            messages: vec![message_pane(theme)],
+            active: 0,
+            theme,
+        }
+    }
+
+    // message pane of the active session
+    pub fn message(&mut self) -> &mut UIParagraphWithScrollbar<'a> {
+        &mut self.messages[self.active]
+    }
+
+    pub fn message_ref(&self) -> &UIParagraphWithScrollbar<'a> {
+        &self.messages[self.active]
+    }
+
+    // add a pane for a new session, the new session is activated
+    pub fn add_session(&mut self) {
+        self.messages.push(message_pane(self.theme));
+        self.activate_session(self.messages.len() - 1);
+    }
+
+    // remove the pane of the given session, the last pane is never removed
+    pub fn remove_session(&mut self, idx: usize) {
+        if self.messages.len() > 1 && idx < self.messages.len() {
+            self.messages.remove(idx);
+            self.activate_session(self.active.min(self.messages.len() - 1));
         }
     }
 
+    // border of the active pane is highlighted when there are multiple sessions
+    pub fn activate_session(&mut self, idx: usize) {
+        self.active = idx;
+        let multiple = self.messages.len() > 1;
+        for (i, message) in self.messages.iter_mut().enumerate() {
+            if multiple && i == idx {
+                message.highlight_border();
+            } else {
+                message.normalise_border();
+            }
+        }
+    }
+
+    // index of the session pane at the given position
+    pub fn session_at(&self, position: Position) -> Option<usize> {
+        self.messages.iter().position(|m| m.contains(position))
+    }
+
+    // render only the message pane of the active session
+    pub fn render_message(&mut self, frame: &mut Frame, area: Rect) {
+        self.message().render(frame, area);
+    }
+
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
         let layout = Layout::vertical([Constraint::Length(9), Constraint::Fill(1)]);
-        let [details, message] = layout.areas(area);
+        let [details, messages] = layout.areas(area);
         self.details.render(frame, details);
-        self.message.render(frame, message);
+
+        let areas = Layout::horizontal(vec![Constraint::Fill(1); self.messages.len()]).split(messages);
+        for (message, area) in self.messages.iter_mut().zip(areas.iter()) {
+            message.render(frame, *area);
+        }
+    }
+}
+
+// Topics without the topics hidden by the settings in the chosen order, along with their
+// partitions, messages & messages produced since the previous refresh of the activity
+fn topics_list(metadata: &Metadata, activity: &Activity, settings: &LayoutSettings) -> (Vec<String>, HashMap<String, String>) {
+    let mut topics = metadata.visible_topics_list(settings.hide_internal_topics, settings.hide_stream_topics);
+    let partitions = metadata.topics().iter()
+        .map(|t| (t.name(), t.partitions().len()))
+        .collect::<HashMap<&str, usize>>();
+    let partitions_of = |t: &str| partitions.get(t).copied().unwrap_or_default();
+
+    // ties are kept in alphabetical order
+    match settings.topic_sort {
+        TopicSort::Name => (),
+        TopicSort::Partitions => topics.sort_by_key(|t| Reverse(partitions_of(t))),
+        TopicSort::Messages => topics.sort_by_key(|t| Reverse(activity.topic(t).map(|a| a.messages))),
+        TopicSort::Activity => topics.sort_by_key(|t| Reverse(activity.topic(t).and_then(|a| a.new_messages))),
     }
+
+    let details = topics.iter()
+        .map(|t| {
+            let columns = match (activity.updated_at(), activity.topic(t)) {
+                (None, _) => format!("{:>4}p", partitions_of(t)),
+                (Some(_), topic_activity) => format!("{:>4}p {:>7} {:>7}", partitions_of(t),
+                    topic_activity.map(|a| compact_count(a.messages)).unwrap_or("-".to_string()),
+                    topic_activity.and_then(|a| a.new_messages).map(|n| format!("+{}", compact_count(n))).unwrap_or("-".to_string())),
+            };
+            (t.clone(), columns)
+        })
+        .collect();
+
+    (topics, details)
+}
+
+// Consumer groups in the chosen order, along with their state, members & lag
+fn groups_list(metadata: &Metadata, activity: &Activity, sort: GroupSort) -> (Vec<String>, HashMap<String, String>) {
+    let mut groups = metadata.consumer_groups().to_vec();
+    groups.sort_by(|a, b| a.name().cmp(b.name()));
+
+    // ties are kept in alphabetical order
+    match sort {
+        GroupSort::Name => (),
+        GroupSort::State => groups.sort_by(|a, b| a.state().cmp(b.state())),
+        GroupSort::Members => groups.sort_by_key(|g| Reverse(g.members_count())),
+        GroupSort::Lag => groups.sort_by_key(|g| Reverse(activity.group_lag(g.name()))),
+    }
+
+    let details = groups.iter()
+        .map(|g| {
+            let lag = activity.group_lag(g.name()).map(compact_count).unwrap_or("-".to_string());
+            (g.name().to_string(), format!("{:<19} {:>3}m {:>7}", g.state(), g.members_count(), lag))
+        })
+        .collect();
+
+    (groups.iter().map(|g| g.name().to_string()).collect(), details)
+}
+
+// Count in a compact form, e.g. 1.2K, 3.4M
+pub fn compact_count(count: i64) -> String {
+    let units = [(1_000_000_000_000, "T"), (1_000_000_000, "B"), (1_000_000, "M"), (1_000, "K")];
+    match units.iter().find(|(size, _)| count.abs() >= *size) {
+        Some((size, unit)) => format!("{:.1}{}", count as f64 / *size as f64, unit),
+        None => count.to_string(),
+    }
+}
+
+fn message_pane<'a>(theme: Theme) -> UIParagraphWithScrollbar<'a> {
+    UIParagraphWithScrollbar::new("Message".to_string(), "".into(), ScrollbarOrientation::VerticalRight, theme)
 }
 
 // Footer Layout
@@ -239,23 +607,90 @@ pub struct FooterLayout<'a> {
     pub mode: UIParagraph<'a>,
     pub footer: UIParagraph<'a>,
     pub input: UIInput<'a>,
+    footer_text: String,
+    warning: Option<String>,
+    // progress of a task running in the background, e.g. an export
+    progress: Option<String>,
+    notification: Option<Notification>,
+    theme: Theme,
 }
 
 impl <'a> FooterLayout<'a> {
-    pub fn new() -> FooterLayout<'a> {
+    pub fn new(keymap: &Keymap, theme: Theme) -> FooterLayout<'a> {
+        let footer_text = format!("Press <{}> for help | Press <{}> to quit", keymap.keys(Action::Help), keymap.keys(Action::Quit));
+
         FooterLayout {
-            mode: UIParagraph::new("".to_string(), Text::default()),
+            mode: UIParagraph::new("".to_string(), Text::default(), theme),
             footer: UIParagraph::new("".to_string(), Text::from(vec![
-                Span::from(APP_FOOTER).gray().into_centered_line(),
-            ])),
-            input: UIInput::new("".to_string()),
+                Span::from(footer_text.clone()).fg(theme.text).into_centered_line(),
+            ]), theme),
+            input: UIInput::new("".to_string(), theme),
+            footer_text,
+            warning: None,
+            progress: None,
+            notification: None,
+            theme,
+        }
+    }
+
+    pub fn update_mode(&mut self, mode: String, read_only: bool) {
+        let mut spans = vec![
+            Span::from(" Mode: ").fg(self.theme.text).bold(),
+            Span::from(mode).bold().fg(self.theme.label)];
+
+        // read-only badge next to the mode
+        if read_only {
+            spans.push(Span::from(" "));
+            spans.push(Span::styled(" read-only ", self.theme.badge_style()));
+        }
+
+        self.mode.update(Text::from(vec![spans.into()]));
+    }
+
+    // warning is displayed along with the footer text until the app exits
+    pub fn set_warning(&mut self, warning: String) {
+        self.warning = Some(warning);
+        self.refresh_footer();
+    }
+
+    // progress is displayed along with the footer text until it is cleared
+    pub fn set_progress(&mut self, progress: Option<String>) {
+        self.progress = progress;
+        self.refresh_footer();
+    }
+
+    // notification is displayed in place of the footer text until it expires
+    pub fn notify(&mut self, notification: Notification) {
+        self.notification = Some(notification);
+        self.refresh_footer();
+    }
+
+    pub fn expire_notification(&mut self) {
+        if self.notification.as_ref().is_some_and(|n| n.expired()) {
+            self.notification = None;
+            self.refresh_footer();
         }
     }
 
-    pub fn update_mode(&mut self, mode: String) {
-        self.mode.update(Text::from(vec![vec![
-                                    Span::from(" Mode: ").gray().bold(),
-                                    Span::from(mode).bold().green()].into()]));
+    fn refresh_footer(&mut self) {
+        let line: Line = match &self.notification {
+            Some(notification) => notification_line(notification, &self.theme),
+            None => {
+                let mut spans = vec![];
+                if let Some(progress) = &self.progress {
+                    spans.push(Span::from(progress.clone()).fg(self.theme.label).bold());
+                    spans.push(Span::from(" | ").fg(self.theme.text));
+                }
+                if let Some(warning) = &self.warning {
+                    spans.push(Span::from(warning.clone()).fg(self.theme.warning).bold());
+                    spans.push(Span::from(" | ").fg(self.theme.text));
+                }
+                spans.push(Span::from(self.footer_text.clone()).fg(self.theme.text));
+                spans.into()
+            },
+        };
+
+        self.footer.update(Text::from(vec![line]).centered());
     }
 
     pub fn handle_input_event(&mut self, event: InputEvent) {
@@ -266,10 +701,14 @@ impl <'a> FooterLayout<'a> {
         self.input.value()
     }
 
-    pub fn set_value(&mut self, value: &'a str) {
+    pub fn set_value(&mut self, value: &str) {
         self.input.set_value(value);
     }
 
+    pub fn set_input_hint(&mut self, hint: Option<String>) {
+        self.input.set_hint(hint);
+    }
+
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
         let layout = Layout::horizontal([Constraint::Percentage(20), Constraint::Percentage(60), Constraint::Percentage(20)]);
         let [mode, key_mappings, input] = layout.areas(area);
@@ -281,34 +720,45 @@ impl <'a> FooterLayout<'a> {
 
 // Help Layout
 pub struct HelpLayout<'a> {
-    help: UIParagraph<'a>,
+    // scrolled as the bindings & the commands don't fit the popup on small terminals
+    help: UIParagraphWithScrollbar<'a>,
 }
 
 impl <'a> HelpLayout<'a> {
-    pub fn new() -> HelpLayout<'a> {
-        let help_text = Text::from(vec![
-            Line::from(Span::from("Help Menu").bold().underlined().green().into_centered_line()),
+    pub fn new(keymap: &Keymap, theme: Theme) -> HelpLayout<'a> {
+        let mut lines = vec![
+            Span::from("Help Menu").bold().underlined().fg(theme.label).into_centered_line(),
             Span::from("").into(),
-            Line::from(Span::from(" Key Mappings:").green()),
+            Line::from(Span::from(" Key Mappings:").fg(theme.label)),
             Span::from("").into(),
-            help_option(" TAB      ", "Navigate between lists"),
-            help_option(" UP/DOWN  ", "Scroll thru the selected lists"),
-            help_option(" M        ", "Scroll down the message pane"),
-            help_option(" N        ", "Scroll up the message pane"),
-            help_option(" RIGHT    ", "Move to next offset"),
-            help_option(" Left     ", "Move to previous offset"),
-            help_option(" :        ", "Enter edit mode"),
-            help_option(" H        ", "Show/Hide help menu"),
-            help_option(" ESC      ", "Exist the application"),
+        ];
+
+        // key mappings are generated from the keymap
+        let key_width = keymap.bindings().map(|(_, keys)| keys.len()).max().unwrap_or_default() + 2;
+        for (action, keys) in keymap.bindings() {
+            lines.push(help_option(format!(" {:<width$}", keys, width = key_width), action.description(), &theme));
+        }
+
+        lines.extend(vec![
             Span::from("").into(),
-            Line::from(Span::from(" Commands (edit mode):").green()),
+            Line::from(Span::from(" Commands (edit mode):").fg(theme.label)),
             Span::from("").into(),
-            help_option(" offset!<num>  ", "Fetches the message at a given offset"),
-            help_option(" ts!<epoch>    ", "Fetches the message for a given timestamp"),
-
         ]);
 
-        let mut paragraph = UIParagraph::new_with_color("Help".to_string(), ratatui::style::Color::Gray, help_text);
+        // commands are generated from the command names
+        let commands = CommandName::iter()
+            .map(|c| (format!(":{} {}", c, c.usage()), c.description()))
+            .collect::<Vec<(String, &str)>>();
+        let command_width = commands.iter().map(|(c, _)| c.len()).max().unwrap_or_default() + 2;
+        for (command, description) in commands {
+            lines.push(help_option(format!(" {:<width$}", command, width = command_width), description, &theme));
+        }
+        lines.push(help_option(format!(" {:<width$}", "TAB", width = command_width), "Completes command, topic & partition", &theme));
+        lines.push(help_option(format!(" {:<width$}", "UP/DOWN", width = command_width), "Browses command history", &theme));
+
+        let help_text = Text::from(lines);
+
+        let mut paragraph = UIParagraphWithScrollbar::new("Help".to_string(), help_text, ScrollbarOrientation::VerticalRight, theme);
         paragraph.highlight_border();
 
         HelpLayout {
@@ -322,13 +772,415 @@ impl <'a> HelpLayout<'a> {
         self.help.render(frame, area);
     }
 
-    
+    pub fn handle_up(&mut self) {
+        self.help.handle_up();
+    }
+
+    pub fn handle_down(&mut self) {
+        self.help.handle_down();
+    }
+}
+
+// Connection Info Layout
+pub struct ConnectionInfoLayout<'a> {
+    info: UIParagraph<'a>,
+}
+
+impl <'a> ConnectionInfoLayout<'a> {
+    pub fn new(connection_info: &ConnectionInfo, theme: Theme) -> ConnectionInfoLayout<'a> {
+        let mut lines = vec![
+            Span::from("Connection Info").bold().underlined().fg(theme.label).into_centered_line(),
+            Span::from("").into(),
+            info_option(" Bootstrap servers : ", connection_info.bootstrap_servers.clone(), &theme),
+            info_option(" Protocol          : ", connection_info.protocol.clone(), &theme),
+        ];
+
+        if connection_info.certificates.is_empty() {
+            lines.push(Span::from("").into());
+            lines.push(Line::from(Span::from(" No certificates configured").fg(theme.text)));
+        }
+
+        for cert in &connection_info.certificates {
+            let expiry = format!("{} ({})", cert.not_after, cert.expiry());
+            let sans = if cert.sans.is_empty() { "-".to_string() } else { cert.sans.join(", ") };
+
+            lines.push(Span::from("").into());
+            lines.push(Line::from(Span::from(format!(" {} ({}):", cert.label, cert.location)).fg(theme.label)));
+            lines.push(info_option("   Subject : ", cert.subject.clone(), &theme));
+            lines.push(info_option("   Issuer  : ", cert.issuer.clone(), &theme));
+            lines.push(info_option("   SANs    : ", sans, &theme));
+
+            // highlight expiry if certificate is about to expire
+            if connection_info.expiry_warning(cert) {
+                lines.push(Line::from(vec![
+                    Span::from("   Expires : ").bold().fg(theme.label),
+                    Span::from(expiry).bold().fg(theme.warning),
+                ]));
+            } else {
+                lines.push(info_option("   Expires : ", expiry, &theme));
+            }
+        }
+
+        let mut paragraph = UIParagraph::new("Connection".to_string(), Text::from(lines), theme);
+        paragraph.highlight_border();
+
+        ConnectionInfoLayout {
+            info: paragraph,
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        // clear existing area before showing connection info dialog
+        frame.render_widget(Clear, area);
+        self.info.render(frame, area);
+    }
+}
+
+// Notification History Layout, latest notifications first
+pub struct NotificationsLayout<'a> {
+    notifications: UIParagraphWithScrollbar<'a>,
+    history: VecDeque<Notification>,
+    theme: Theme,
+}
+
+impl <'a> NotificationsLayout<'a> {
+    pub fn new(theme: Theme) -> NotificationsLayout<'a> {
+        let mut layout = NotificationsLayout {
+            notifications: UIParagraphWithScrollbar::new("Notifications".to_string(), Text::default(), ScrollbarOrientation::VerticalRight, theme),
+            history: VecDeque::new(),
+            theme,
+        };
+
+        layout.refresh();
+        layout
+    }
+
+    pub fn push(&mut self, notification: Notification) {
+        self.history.push_front(notification);
+        self.history.truncate(NOTIFICATION_HISTORY_SIZE);
+        self.refresh();
+    }
+
+    pub fn handle_up(&mut self) {
+        self.notifications.handle_up();
+    }
+
+    pub fn handle_down(&mut self) {
+        self.notifications.handle_down();
+    }
+
+    fn refresh(&mut self) {
+        let lines = if self.history.is_empty() {
+            vec![Line::from(Span::from(" No notifications").fg(self.theme.text))]
+        } else {
+            self.history.iter()
+                .map(|n| notification_line(n, &self.theme))
+                .collect()
+        };
+
+        self.notifications.update(Text::from(lines));
+        self.notifications.highlight_border();
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        // clear existing area before showing notification history dialog
+        frame.render_widget(Clear, area);
+        self.notifications.render(frame, area);
+    }
+}
+
+// Bookmarks Layout, bookmarks of the cluster along with their notes
+pub struct BookmarksLayout<'a> {
+    list: UIList<'a>,
+    bookmarks: Vec<Bookmark>,
+}
+
+impl <'a> BookmarksLayout<'a> {
+    pub fn new(theme: Theme) -> BookmarksLayout<'a> {
+        let mut list = UIList::new(BOOKMARKS_LIST.to_string(), vec![], theme);
+        list.highlight_border();
+
+        BookmarksLayout {
+            list,
+            bookmarks: vec![],
+        }
+    }
+
+    // bookmarks are listed by their positions, the notes are shown next to them
+    pub fn update(&mut self, bookmarks: Vec<Bookmark>) {
+        let details = bookmarks.iter()
+            .filter(|b| !b.note.is_empty())
+            .map(|b| (b.position(), b.note.clone()))
+            .collect::<HashMap<String, String>>();
+
+        self.list.update(bookmarks.iter().map(|b| b.position()).collect());
+        self.list.set_details(details);
+        if self.list.state().is_none() && !bookmarks.is_empty() {
+            self.list.select(Some(0));
+        }
+
+        self.bookmarks = bookmarks;
+    }
+
+    pub fn handle_navigation(&mut self, direction: Direction) {
+        self.list.handle_navigation(direction);
+    }
+
+    pub fn selected(&self) -> Option<&Bookmark> {
+        let position = self.list.selected_item()?;
+        self.bookmarks.iter().find(|b| b.position() == position)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        // clear existing area before showing bookmarks dialog
+        frame.render_widget(Clear, area);
+        self.list.render(frame, area);
+    }
+}
+
+// Navigation History Layout, locations visited in the active session, latest first
+pub struct HistoryLayout<'a> {
+    list: UIList<'a>,
+    // number of the locations listed, rows are in the reverse order of the locations
+    len: usize,
+}
+
+impl <'a> HistoryLayout<'a> {
+    pub fn new(theme: Theme) -> HistoryLayout<'a> {
+        let mut list = UIList::new(HISTORY_LIST.to_string(), vec![], theme);
+        list.highlight_border();
+
+        HistoryLayout {
+            list,
+            len: 0,
+        }
+    }
+
+    // locations are listed with the time they were visited at & the current location is marked, e.g. "* 10:32:01 orders/3@1200"
+    // keys of the messages are shown next to them
+    pub fn update(&mut self, history: &NavigationHistory) {
+        let mut items = vec![];
+        let mut details = HashMap::new();
+        for (idx, location) in history.locations().iter().enumerate().rev() {
+            let marker = if history.position() == Some(idx) { "*" } else { " " };
+            let item = format!("{} {} {}@{}", marker, location.visited_at.format("%H:%M:%S"), location.partition, location.offset);
+            if let Some(key) = &location.key {
+                details.insert(item.clone(), key_preview(key));
+            }
+            items.push(item);
+        }
+
+        self.len = items.len();
+        self.list.update(items);
+        self.list.set_details(details);
+
+        // current location is selected
+        let row = history.position().map(|p| self.len - 1 - p);
+        self.list.select(row);
+    }
+
+    pub fn handle_navigation(&mut self, direction: Direction) {
+        self.list.handle_navigation(direction);
+    }
+
+    // index of the selected location in the history
+    pub fn selected(&self) -> Option<usize> {
+        self.list.state()
+            .filter(|row| *row < self.len)
+            .map(|row| self.len - 1 - row)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        // clear existing area before showing navigation history dialog
+        frame.render_widget(Clear, area);
+        self.list.render(frame, area);
+    }
+}
+
+fn key_preview(key: &str) -> String {
+    let key = key.replace('\n', " ");
+    match key.char_indices().nth(KEY_PREVIEW_LENGTH) {
+        Some((idx, _)) => format!("{}...", &key[..idx]),
+        None => key,
+    }
+}
+
+// Generate a line for a given notification, e.g. "10:32:01 ERROR invalid offset"
+fn notification_line<'a>(notification: &Notification, theme: &Theme) -> Line<'a> {
+    let level_color = match notification.level {
+        Level::Info => theme.label,
+        Level::Warn => theme.warning,
+        Level::Error => theme.error,
+    };
+
+    Line::from(vec![
+        Span::from(format!(" {} ", notification.time())).fg(theme.text),
+        Span::from(format!("{:<5} ", notification.level.to_string())).bold().fg(level_color),
+        Span::from(notification.message.clone()),
+    ])
+}
+
+// Log Layout, shows the records of the in-memory log buffer
+pub struct LogsLayout<'a> {
+    logs: UIParagraphWithScrollbar<'a>,
+    buffer: LogBuffer,
+    // records below this level are hidden
+    level: LevelFilter,
+    search: Option<String>,
+    // keep scrolling to the latest records
+    follow: bool,
+    // total records of the buffer when last refreshed
+    refreshed_total: u64,
+    theme: Theme,
+}
+
+impl <'a> LogsLayout<'a> {
+    pub fn new(buffer: LogBuffer, theme: Theme) -> LogsLayout<'a> {
+        let mut layout = LogsLayout {
+            logs: UIParagraphWithScrollbar::new("Logs".to_string(), Text::default(), ScrollbarOrientation::VerticalRight, theme).without_wrap(),
+            buffer,
+            level: LevelFilter::Trace,
+            search: None,
+            follow: true,
+            refreshed_total: 0,
+            theme,
+        };
+
+        layout.refresh();
+        layout
+    }
+
+    // picks up the new records while following
+    pub fn refresh_if_following(&mut self) {
+        if self.follow && self.buffer.total() != self.refreshed_total {
+            self.refresh();
+        }
+    }
+
+    // cycle through the levels, each level hides the records below it
+    pub fn cycle_level(&mut self) {
+        self.level = match self.level {
+            LevelFilter::Trace => LevelFilter::Debug,
+            LevelFilter::Debug => LevelFilter::Info,
+            LevelFilter::Info => LevelFilter::Warn,
+            LevelFilter::Warn => LevelFilter::Error,
+            _ => LevelFilter::Trace,
+        };
+        self.refresh();
+    }
+
+    pub fn toggle_follow(&mut self) {
+        self.follow = !self.follow;
+        self.refresh();
+    }
+
+    pub fn search(&self) -> Option<&str> {
+        self.search.as_deref()
+    }
+
+    pub fn set_search(&mut self, search: Option<String>) {
+        self.search = search;
+        self.refresh();
+    }
+
+    // scrolling pauses following the latest records
+    pub fn handle_up(&mut self) {
+        self.follow = false;
+        self.logs.handle_up();
+    }
+
+    pub fn handle_down(&mut self) {
+        self.follow = false;
+        self.logs.handle_down();
+    }
+
+    fn title(&self, matches: usize) -> String {
+        let mut title = format!("Logs ({}) level:{}", matches, self.level);
+        if let Some(search) = &self.search {
+            title.push_str(&format!(" /{}", search));
+        }
+        title.push_str(if self.follow { " [following]" } else { " [paused]" });
+        title
+    }
+
+    fn refresh(&mut self) {
+        self.refreshed_total = self.buffer.total();
+
+        let search = self.search.as_deref().filter(|s| !s.is_empty()).map(|s| s.to_ascii_lowercase());
+        let lines = self.buffer.records().iter()
+            .filter(|r| r.level <= self.level)
+            .filter(|r| search.as_ref().is_none_or(|s| log_line_text(r).to_ascii_lowercase().contains(s)))
+            .map(|r| log_line(r, search.as_deref(), &self.theme))
+            .collect::<Vec<Line>>();
+
+        let title = self.title(lines.len());
+        self.logs.update_with_title(title, Text::from(lines));
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        // borders take up 2 lines
+        if self.follow {
+            self.logs.scroll_to_end(area.height.saturating_sub(2));
+        }
+        self.logs.render(frame, area);
+    }
+}
+
+fn log_line_text(record: &LogRecord) -> String {
+    format!("{} {:<5} [{}] {}", record.timestamp, record.level, record.target, record.message.replace('\n', " "))
+}
+
+// Generate a line for a given log record with the level colored & the search highlighted
+fn log_line<'a>(record: &LogRecord, search: Option<&str>, theme: &Theme) -> Line<'a> {
+    let level_color = match record.level {
+        log::Level::Error => theme.error,
+        log::Level::Warn => theme.warning,
+        log::Level::Info => theme.label,
+        _ => theme.text,
+    };
+
+    let text = log_line_text(record);
+    let level_start = record.timestamp.len() + 1;
+    let level_end = level_start + 5;
+
+    let mut spans = vec![
+        Span::from(text[..level_start].to_string()).fg(theme.text),
+        Span::from(text[level_start..level_end].to_string()).bold().fg(level_color),
+    ];
+
+    // search is case insensitive, ascii lowercase keeps the byte positions intact
+    let rest = &text[level_end..];
+    match search {
+        Some(search) => {
+            let lower = rest.to_ascii_lowercase();
+            let mut start = 0;
+            while let Some(idx) = lower[start..].find(search) {
+                let match_start = start + idx;
+                let match_end = match_start + search.len();
+                spans.push(Span::from(rest[start..match_start].to_string()));
+                spans.push(Span::styled(rest[match_start..match_end].to_string(), theme.match_style()));
+                start = match_end;
+            }
+            spans.push(Span::from(rest[start..].to_string()));
+        },
+        None => spans.push(Span::from(rest.to_string())),
+    }
+
+    Line::from(spans)
+}
+
+// Generate a line for a given connection info option
+fn info_option<'a>(key: &'a str, value: String, theme: &Theme) -> Line<'a> {
+    Line::from(vec![
+        Span::from(key).bold().fg(theme.label),
+        Span::from(value),
+    ])
 }
 
 // Generate a line for a given help option
-fn help_option<'a>(key: &'a str, purpose: &'a str) -> Line<'a> {
+fn help_option<'a>(key: String, purpose: &'a str, theme: &Theme) -> Line<'a> {
     Line::from(vec![
-        Span::from(key).bold().green().into(), 
-        Span::from(purpose).into(),
+        Span::from(key).bold().fg(theme.label),
+        Span::from(purpose),
     ])
 }
\ No newline at end of file
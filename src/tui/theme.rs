@@ -0,0 +1,187 @@
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
+
+use ratatui::style::{palette::tailwind, Color, Modifier, Style};
+use serde::Deserialize;
+
+use crate::config::{self, ConfigError, ThemePreset};
+
+const THEME_FILE: &str = "theme.toml";
+
+// https://no-color.org, disables colors when set to a non empty value
+const NO_COLOR: &str = "NO_COLOR";
+
+// Colors used across the layouts and widgets
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    // border of the widgets
+    pub border: Color,
+    // border of the focused widget & the selected item
+    pub highlight: Color,
+    // chars matching the filter
+    pub matched: Color,
+    // application title
+    pub title: Color,
+    // keys & labels, e.g. in help menu
+    pub label: Color,
+    // secondary text
+    pub text: Color,
+    pub warning: Color,
+    pub error: Color,
+    // background of the read-only badge
+    pub badge: Color,
+    // background of the selected tab
+    pub tab: Color,
+}
+
+impl Theme {
+    fn preset(preset: ThemePreset) -> Theme {
+        match preset {
+            ThemePreset::Dark => Theme {
+                border: Color::Green,
+                highlight: Color::Yellow,
+                matched: Color::Cyan,
+                title: Color::Green,
+                label: Color::Green,
+                text: Color::Gray,
+                warning: Color::Yellow,
+                error: Color::Red,
+                badge: Color::Red,
+                tab: tailwind::GREEN.c700,
+            },
+            ThemePreset::Light => Theme {
+                border: tailwind::SLATE.c500,
+                highlight: tailwind::BLUE.c700,
+                matched: tailwind::FUCHSIA.c700,
+                title: tailwind::BLUE.c800,
+                label: tailwind::BLUE.c800,
+                text: tailwind::SLATE.c700,
+                warning: tailwind::ORANGE.c700,
+                error: tailwind::RED.c700,
+                badge: tailwind::RED.c600,
+                tab: tailwind::BLUE.c200,
+            },
+            ThemePreset::HighContrast => Theme {
+                border: Color::White,
+                highlight: Color::LightYellow,
+                matched: Color::LightCyan,
+                title: Color::White,
+                label: Color::LightGreen,
+                text: Color::White,
+                warning: Color::LightYellow,
+                error: Color::LightRed,
+                badge: Color::LightRed,
+                tab: Color::Blue,
+            },
+            // modifiers are used instead of colors, see the style functions
+            ThemePreset::NoColor => Theme {
+                border: Color::Reset,
+                highlight: Color::Reset,
+                matched: Color::Reset,
+                title: Color::Reset,
+                label: Color::Reset,
+                text: Color::Reset,
+                warning: Color::Reset,
+                error: Color::Reset,
+                badge: Color::Reset,
+                tab: Color::Reset,
+            },
+        }
+    }
+
+    // Load theme from the given file or from the default location if it exists
+    // preset passed as an argument takes precedence over the preset in the file
+    pub fn load(file: Option<&str>, preset: Option<ThemePreset>) -> Result<Theme, ConfigError> {
+        let location = match file {
+            Some(f) => Some(PathBuf::from(f)),
+            None => config::config_dir()
+                .map(|d| d.join(THEME_FILE))
+                .filter(|f| f.exists()),
+        };
+
+        let theme_file = match location {
+            Some(location) => {
+                log::info!("loading theme from {}", location.display());
+                let content = std::fs::read_to_string(&location)
+                    .map_err(|err| ConfigError::new(&format!("unable to read theme {}: {}", location.display(), err)))?;
+                toml::from_str::<ThemeFile>(&content)
+                    .map_err(|err| ConfigError::new(&format!("unable to parse theme {}: {}", location.display(), err)))?
+            },
+            None => ThemeFile::default(),
+        };
+
+        let no_color = std::env::var(NO_COLOR).is_ok_and(|v| !v.is_empty());
+        let preset = preset
+            .or(theme_file.preset)
+            .unwrap_or(if no_color { ThemePreset::NoColor } else { ThemePreset::Dark });
+
+        // override preset colors with the ones from the file
+        let mut theme = Theme::preset(preset);
+        for (name, value) in theme_file.colors {
+            let color = Color::from_str(&value)
+                .map_err(|_| ConfigError::new(&format!("invalid color {} for {} in theme", value, name)))?;
+
+            match name.as_str() {
+                "border" => theme.border = color,
+                "highlight" => theme.highlight = color,
+                "matched" => theme.matched = color,
+                "title" => theme.title = color,
+                "label" => theme.label = color,
+                "text" => theme.text = color,
+                "warning" => theme.warning = color,
+                "error" => theme.error = color,
+                "badge" => theme.badge = color,
+                "tab" => theme.tab = color,
+                _ => return Err(ConfigError::new(&format!("invalid color name {} in theme", name))),
+            }
+        }
+
+        Ok(theme)
+    }
+
+    // Border style of a widget, focused widget gets a bold border
+    pub fn border_style(&self, focused: bool) -> Style {
+        if focused {
+            Style::new().fg(self.highlight).add_modifier(Modifier::BOLD)
+        } else {
+            Style::new().fg(self.border)
+        }
+    }
+
+    // Style of the selected item, reversed if there is no color to tell it apart
+    pub fn selection_style(&self) -> Style {
+        let style = Style::new().fg(self.highlight).add_modifier(Modifier::BOLD);
+        if self.highlight == Color::Reset {
+            return style.add_modifier(Modifier::REVERSED);
+        }
+
+        style
+    }
+
+    pub fn match_style(&self) -> Style {
+        Style::new().fg(self.matched).add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    }
+
+    pub fn tab_style(&self) -> Style {
+        if self.tab == Color::Reset {
+            return Style::new().add_modifier(Modifier::REVERSED);
+        }
+
+        Style::new().bg(self.tab)
+    }
+
+    pub fn badge_style(&self) -> Style {
+        if self.badge == Color::Reset {
+            return Style::new().add_modifier(Modifier::BOLD | Modifier::REVERSED);
+        }
+
+        Style::new().fg(Color::Black).bg(self.badge).add_modifier(Modifier::BOLD)
+    }
+}
+
+// Theme file, colors override the colors of the preset
+#[derive(Debug, Default, Deserialize)]
+struct ThemeFile {
+    preset: Option<ThemePreset>,
+    #[serde(default)]
+    colors: HashMap<String, String>,
+}
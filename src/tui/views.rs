@@ -0,0 +1,374 @@
+use std::collections::BTreeMap;
+
+use chrono::Local;
+use ratatui::{layout::{Constraint, Layout, Position, Rect}, style::Stylize, text::{Line, Span, Text}, widgets::ScrollbarOrientation, Frame};
+use strum::{Display, EnumCount, EnumIter, IntoEnumIterator};
+
+use crate::kafka::{activity::Activity, metadata::{ConsumerGroup, Metadata}, producer::ProducerRecord};
+
+use super::single_layout::compact_count;
+use super::theme::Theme;
+use super::widgets::{AppWidget, Direction, InputEvent, UIInput, UIList, UIParagraph, UIParagraphWithScrollbar};
+
+// Titles of the tab bar, in the same order as the views
+pub const VIEW_TITLES: [&str; View::COUNT] = ["Consumer", "Producer", "Admin", "Groups", "Stats"];
+
+const GROUPS_LIST: &str = "Consumer Groups";
+// Deliveries shown in the producer view, older deliveries are dropped
+const MAX_DELIVERIES: usize = 100;
+
+// Top level views of the app, each view has its own layout & state
+#[derive(Clone, Copy, Debug, Default, Display, PartialEq, EnumIter, EnumCount)]
+pub enum View {
+    #[default]
+    Consumer,
+    Producer,
+    Admin,
+    Groups,
+    Stats,
+}
+
+impl View {
+    pub fn idx(&self) -> usize {
+        View::iter().position(|v| v == *self).unwrap_or_default()
+    }
+
+    pub fn next(&self) -> View {
+        View::iter().cycle().nth(self.idx() + 1).unwrap_or_default()
+    }
+
+    pub fn prev(&self) -> View {
+        View::iter().cycle().nth(self.idx() + View::COUNT - 1).unwrap_or_default()
+    }
+
+    pub fn from_idx(idx: usize) -> View {
+        View::iter().nth(idx).unwrap_or_default()
+    }
+}
+
+// Field of the producer form
+#[derive(Clone, Copy, Debug, Default, PartialEq, EnumIter, EnumCount)]
+pub enum ProducerField {
+    #[default]
+    Topic,
+    Key,
+    Payload,
+}
+
+// Producer view, form to produce a message along with the deliveries of the produced messages
+pub struct ProducerLayout<'a> {
+    topic: UIInput<'a>,
+    key: UIInput<'a>,
+    payload: UIInput<'a>,
+    deliveries: UIParagraphWithScrollbar<'a>,
+    field: ProducerField,
+    // newest delivery first
+    delivery_lines: Vec<Line<'a>>,
+    theme: Theme,
+}
+
+impl <'a> ProducerLayout<'a> {
+    pub fn new(theme: Theme, read_only: bool) -> ProducerLayout<'a> {
+        let info = match read_only {
+            true => Span::from("Producing messages is disabled in read-only mode").fg(theme.warning),
+            false => Span::from("Press ENTER to edit the form, TAB to switch fields, ENTER to send & ESC to stop editing").fg(theme.text),
+        };
+
+        ProducerLayout {
+            topic: UIInput::new("Topic".to_string(), theme),
+            key: UIInput::new("Key".to_string(), theme),
+            payload: UIInput::new("Payload".to_string(), theme),
+            deliveries: UIParagraphWithScrollbar::new("Deliveries".to_string(), Text::from(Line::from(info)), ScrollbarOrientation::VerticalRight, theme),
+            field: ProducerField::default(),
+            delivery_lines: vec![],
+            theme,
+        }
+    }
+
+    // highlight the field being edited, none while the form is not edited
+    pub fn set_editing(&mut self, editing: bool) {
+        let field = self.field;
+        for f in ProducerField::iter() {
+            match editing && f == field {
+                true => self.input(f).highlight_border(),
+                false => self.input(f).normalise_border(),
+            }
+        }
+    }
+
+    // Switch to the next field or the previous field in reverse
+    pub fn switch_field(&mut self, reverse: bool) {
+        let idx = ProducerField::iter().position(|f| f == self.field).unwrap_or_default();
+        let idx = if reverse { idx + ProducerField::COUNT - 1 } else { idx + 1 };
+        self.field = ProducerField::iter().nth(idx % ProducerField::COUNT).unwrap_or_default();
+        self.set_editing(true);
+    }
+
+    pub fn handle_input_event(&mut self, input_event: InputEvent) {
+        self.input(self.field).handle_event(input_event);
+    }
+
+    // Topic & the record of the form, an empty key is not sent & an empty payload produces a tombstone
+    pub fn record(&mut self) -> Result<(String, ProducerRecord), String> {
+        let topic = self.topic.value().trim().to_string();
+        if topic.is_empty() {
+            return Err("topic is required to produce a message".to_string());
+        }
+
+        let (key, payload) = (self.key.value(), self.payload.value());
+        let record = ProducerRecord {
+            key: Some(key).filter(|k| !k.is_empty()),
+            payload: Some(payload).filter(|p| !p.is_empty()),
+            ..Default::default()
+        };
+        Ok((topic, record))
+    }
+
+    // payload is cleared once the message is sent, so that the next message can be typed
+    pub fn clear_payload(&mut self) {
+        self.payload.handle_event(InputEvent::Reset);
+    }
+
+    // Add the outcome of a delivery on top of the deliveries
+    pub fn push_delivery(&mut self, topic: &str, result: Result<(i32, i64), String>) {
+        let time = Local::now().format("%H:%M:%S").to_string();
+        let line = match result {
+            Ok((partition, offset)) => Line::from(vec![
+                Span::from(format!("{} ", time)).fg(self.theme.text),
+                Span::from(format!("delivered to {}/{} at offset {}", topic, partition, offset)),
+            ]),
+            Err(err) => Line::from(vec![
+                Span::from(format!("{} ", time)).fg(self.theme.text),
+                Span::from(format!("delivery to {} failed: {}", topic, err)).fg(self.theme.error),
+            ]),
+        };
+
+        self.delivery_lines.insert(0, line);
+        self.delivery_lines.truncate(MAX_DELIVERIES);
+        self.deliveries.update_with_title(format!("Deliveries ({})", self.delivery_lines.len()), Text::from(self.delivery_lines.clone()));
+    }
+
+    pub fn scroll_deliveries(&mut self, direction: Direction) {
+        match direction {
+            Direction::UP => self.deliveries.handle_up(),
+            _ => self.deliveries.handle_down(),
+        }
+    }
+
+    fn input(&mut self, field: ProducerField) -> &mut UIInput<'a> {
+        match field {
+            ProducerField::Topic => &mut self.topic,
+            ProducerField::Key => &mut self.key,
+            ProducerField::Payload => &mut self.payload,
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [topic, key, payload, deliveries] = Layout::vertical([Constraint::Length(3), Constraint::Length(3), Constraint::Length(3), Constraint::Fill(1)]).areas(area);
+        self.topic.render(frame, topic);
+        self.key.render(frame, key);
+        self.payload.render(frame, payload);
+        self.deliveries.render(frame, deliveries);
+    }
+}
+
+// Admin view, health of the brokers & the topics of the cluster
+pub struct AdminLayout<'a> {
+    pub brokers: UIParagraphWithScrollbar<'a>,
+    pub topics: UIParagraphWithScrollbar<'a>,
+    theme: Theme,
+}
+
+impl <'a> AdminLayout<'a> {
+    pub fn new(theme: Theme) -> AdminLayout<'a> {
+        AdminLayout {
+            brokers: UIParagraphWithScrollbar::new("Brokers".to_string(), Text::default(), ScrollbarOrientation::VerticalRight, theme),
+            topics: UIParagraphWithScrollbar::new("Topic Health".to_string(), Text::default(), ScrollbarOrientation::VerticalRight, theme),
+            theme,
+        }
+    }
+
+    pub fn refresh(&mut self, metadata: &Metadata) {
+        let brokers = metadata.brokers().iter()
+            .map(|b| Line::from(vec![
+                Span::from(format!("{:<40}", b.name())).bold().fg(self.theme.label),
+                Span::from(format!("leader of {} partitions, ", metadata.no_of_partitions_for_broker(b.id()))),
+                Span::from(format!("replica of {} partitions", metadata.no_of_replicas_for_broker(b.id()))),
+            ]))
+            .collect::<Vec<Line>>();
+        self.brokers.update_with_title(format!("Brokers ({})", brokers.len()), Text::from(brokers));
+
+        // only the topics with unhealthy partitions are listed
+        let mut topics = vec![];
+        for topic in metadata.topics() {
+            let offline = topic.partitions().iter().filter(|p| p.is_offline()).count();
+            let under_replicated = topic.partitions().iter().filter(|p| p.is_under_replicated()).count();
+            if offline == 0 && under_replicated == 0 {
+                continue;
+            }
+
+            topics.push(Line::from(vec![
+                Span::from(format!("{:<40}", topic.name())).bold().fg(self.theme.label),
+                Span::from(format!("{} offline, ", offline)).fg(if offline > 0 { self.theme.error } else { self.theme.text }),
+                Span::from(format!("{} under-replicated", under_replicated)).fg(if under_replicated > 0 { self.theme.warning } else { self.theme.text }),
+            ]));
+        }
+
+        if topics.is_empty() {
+            topics.push(Span::from("All partitions are online & in sync").fg(self.theme.text).into());
+        }
+        self.topics.update(Text::from(topics));
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [brokers, topics] = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+        self.brokers.render(frame, brokers);
+        self.topics.render(frame, topics);
+    }
+}
+
+// Groups view, consumer groups along with their members
+pub struct GroupsLayout<'a> {
+    pub groups: UIList<'a>,
+    pub details: UIParagraphWithScrollbar<'a>,
+    consumer_groups: Vec<ConsumerGroup>,
+    activity: Activity,
+    theme: Theme,
+}
+
+impl <'a> GroupsLayout<'a> {
+    pub fn new(theme: Theme) -> GroupsLayout<'a> {
+        let mut groups = UIList::new(GROUPS_LIST.to_string(), vec![], theme);
+        groups.highlight_border();
+
+        GroupsLayout {
+            groups,
+            details: UIParagraphWithScrollbar::new("Group".to_string(), Text::default(), ScrollbarOrientation::VerticalRight, theme),
+            consumer_groups: vec![],
+            activity: Activity::default(),
+            theme,
+        }
+    }
+
+    pub fn refresh(&mut self, metadata: &Metadata, activity: &Activity) {
+        self.consumer_groups = metadata.consumer_groups().to_vec();
+        self.activity = activity.clone();
+        self.groups.update(metadata.consumer_group_lists());
+        self.refresh_details();
+    }
+
+    pub fn handle_navigation(&mut self, direction: Direction) {
+        self.groups.handle_navigation(direction);
+        self.refresh_details();
+    }
+
+    // select the clicked group, returns true if a group got selected
+    pub fn handle_click(&mut self, position: Position) -> bool {
+        if !self.groups.contains(position) || !self.groups.handle_click(position) {
+            return false;
+        }
+
+        self.refresh_details();
+        true
+    }
+
+    fn refresh_details(&mut self) {
+        let selected = self.groups.selected_item();
+        let Some(group) = self.consumer_groups.iter().find(|g| Some(g.name()) == selected.as_deref()) else {
+            self.details.update(Text::default());
+            return;
+        };
+
+        let mut lines = vec![
+            detail_line("Group: ", group.name().to_string(), &self.theme),
+            detail_line("State: ", group.state().to_string(), &self.theme),
+            detail_line("Protocol Type: ", group.protocol_type().to_string(), &self.theme),
+            detail_line("Protocol: ", group.protocol().to_string(), &self.theme),
+            detail_line("Members: ", group.members_count().to_string(), &self.theme),
+            detail_line("Lag: ", self.activity.group_lag(group.name()).map(|l| l.to_string()).unwrap_or("-".to_string()), &self.theme),
+        ];
+
+        for member in group.members() {
+            lines.push(Line::from(""));
+            lines.push(detail_line("  Member: ", member.id().to_string(), &self.theme));
+            lines.push(detail_line("  Client: ", format!("{}@{}", member.client_id(), member.client_host()), &self.theme));
+        }
+
+        self.details.update(Text::from(lines));
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let [groups, details] = Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(area);
+        self.groups.render(frame, groups);
+        self.details.render(frame, details);
+    }
+}
+
+// Stats view, counts across the cluster
+pub struct StatsLayout<'a> {
+    stats: UIParagraph<'a>,
+    theme: Theme,
+}
+
+impl <'a> StatsLayout<'a> {
+    pub fn new(theme: Theme) -> StatsLayout<'a> {
+        StatsLayout {
+            stats: UIParagraph::new("Stats".to_string(), Text::default(), theme),
+            theme,
+        }
+    }
+
+    pub fn refresh(&mut self, metadata: &Metadata, activity: &Activity) {
+        let partitions = metadata.topics().iter().flat_map(|t| t.partitions()).collect::<Vec<_>>();
+        let replicas = partitions.iter().map(|p| p.replicas().len()).sum::<usize>();
+
+        // groups by their state, e.g. Stable: 3
+        let mut group_states = BTreeMap::new();
+        for group in metadata.consumer_groups() {
+            *group_states.entry(group.state().to_string()).or_insert(0) += 1;
+        }
+
+        let mut lines = vec![
+            detail_line("Brokers: ", metadata.brokers().len().to_string(), &self.theme),
+            detail_line("Topics: ", metadata.topics().len().to_string(), &self.theme),
+            detail_line("Partitions: ", partitions.len().to_string(), &self.theme),
+            detail_line("Replicas: ", replicas.to_string(), &self.theme),
+            detail_line("Offline Partitions: ", partitions.iter().filter(|p| p.is_offline()).count().to_string(), &self.theme),
+            detail_line("Under-replicated Partitions: ", partitions.iter().filter(|p| p.is_under_replicated()).count().to_string(), &self.theme),
+            detail_line("Consumer Groups: ", metadata.consumer_groups().len().to_string(), &self.theme),
+        ];
+
+        // messages are known once the activity is fetched in the background
+        if activity.updated_at().is_some() {
+            let messages = metadata.topics().iter()
+                .filter_map(|t| activity.topic(t.name()))
+                .map(|a| a.messages)
+                .sum::<i64>();
+            lines.insert(4, detail_line("Messages: ", compact_count(messages), &self.theme));
+        }
+
+        for (state, count) in group_states {
+            lines.push(detail_line(&format!("  {}: ", state), count.to_string(), &self.theme));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Span::from(format!("Updated at {}", Local::now().format("%H:%M:%S"))).fg(self.theme.text).into());
+        if let Some(updated_at) = activity.updated_at() {
+            lines.push(Span::from(format!("Activity updated at {}", updated_at.format("%H:%M:%S"))).fg(self.theme.text).into());
+        }
+
+        self.stats.update(Text::from(lines));
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        self.stats.render(frame, area);
+    }
+}
+
+// Generate a line for a given detail of a view
+fn detail_line<'a>(key: &str, value: String, theme: &Theme) -> Line<'a> {
+    Line::from(vec![
+        Span::from(key.to_string()).bold().fg(theme.label),
+        Span::from(value),
+    ])
+}
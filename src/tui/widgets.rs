@@ -1,12 +1,16 @@
 use std::char;
+use std::collections::{HashMap, HashSet};
 
 use ratatui::{
-    layout::Constraint, prelude::Rect, style::{palette::tailwind, Color, Modifier, Style, Stylize}, symbols, text::{self, Span, Text}, widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState, Tabs, Wrap}, Frame
+    layout::{Constraint, Position}, prelude::Rect, style::Stylize, symbols, text::{self, Span, Text}, widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState, Tabs, Wrap}, Frame
 };
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
 use tui_input::{Input, InputRequest};
 
-pub const HIGHLIGHT_COLOR: Color = Color::Yellow;
-pub const NORMAL_COLOR: Color = Color::Green;
+use super::theme::Theme;
+
+// Items longer than this push their details out of alignment
+const MAX_DETAILS_OFFSET: usize = 48;
 
 pub enum Direction {
     UP,
@@ -19,6 +23,12 @@ pub trait AppWidget {
     fn render(&mut self, frame: &mut Frame, area: Rect);
     fn highlight_border(&mut self);
     fn normalise_border(&mut self);
+    fn area(&self) -> Rect;
+
+    // whether the given position lies within the widget, used for mouse hit-testing
+    fn contains(&self, position: Position) -> bool {
+        self.area().contains(position)
+    }
 }
 
 // UIWidget contains the specific widget type and the Rect which holds the Widget
@@ -26,16 +36,18 @@ pub trait AppWidget {
 pub struct UIBlock<'a> {
     block: Block<'a>,
     area: Rect,
+    theme: Theme,
 }
 
 impl <'a> UIBlock<'a> {
-    pub fn new(name: &'a str) -> UIBlock<'a> {
+    pub fn new(name: &'a str, theme: Theme) -> UIBlock<'a> {
         UIBlock{
             area: Rect::default(),
             block: Block::default()
                 .borders(Borders::ALL)
                 .border_set(symbols::border::ROUNDED)
-                .border_style(Style::new().fg(NORMAL_COLOR)).title(name)
+                .border_style(theme.border_style(false)).title(name),
+            theme,
         }
     }
 }
@@ -47,11 +59,15 @@ impl <'a> AppWidget for UIBlock<'a> {
     }
 
     fn highlight_border(&mut self) {
-        self.block = self.block.clone().border_style(Style::new().fg(HIGHLIGHT_COLOR))
+        self.block = self.block.clone().border_style(self.theme.border_style(true))
     }
 
     fn normalise_border(&mut self) {
-        self.block = self.block.clone().border_style(Style::new().fg(NORMAL_COLOR));
+        self.block = self.block.clone().border_style(self.theme.border_style(false));
+    }
+
+    fn area(&self) -> Rect {
+        self.area
     }
 }
 
@@ -64,10 +80,7 @@ pub struct UITabs<'a> {
 }
 
 impl <'a> UITabs<'a> {
-    pub fn new(name: &'a str, titles: &'a[&'a str]) -> UITabs<'a> {
-
-        let highlight_style = (Color::default(), tailwind::GREEN.c700);
-
+    pub fn new(name: &'a str, titles: &'a[&'a str], theme: Theme) -> UITabs<'a> {
         UITabs {
             titles,
             state: 0,
@@ -75,9 +88,9 @@ impl <'a> UITabs<'a> {
             tabs: Tabs::new(titles.to_vec())
                 .block(Block::default()
                        .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
-                       .border_style(Style::new().fg(NORMAL_COLOR)).title(name))
+                       .border_style(theme.border_style(false)).title(name))
                 .select(0)
-                .highlight_style(highlight_style),
+                .highlight_style(theme.tab_style()),
         }
     }
 
@@ -102,6 +115,22 @@ impl <'a> UITabs<'a> {
             self.select(self.state.saturating_add(1));
         }
     }
+
+    // select the tab at the clicked position, returns true if a tab got selected
+    pub fn handle_click(&mut self, position: Position) -> bool {
+        // titles are padded by a space on either side & separated by a divider, first column is the border
+        let mut x = self.area.x + 1;
+        for (idx, title) in self.titles.iter().enumerate() {
+            let width = title.chars().count() as u16 + 2;
+            if position.x >= x && position.x < x + width {
+                self.select(idx);
+                return true;
+            }
+            x += width + 1;
+        }
+
+        false
+    }
 }
 
 impl <'a> AppWidget for UITabs<'a> {
@@ -117,49 +146,130 @@ impl <'a> AppWidget for UITabs<'a> {
     fn normalise_border(&mut self) {
         // No implementation required
     }
+
+    fn area(&self) -> Rect {
+        self.area
+    }
 }
 
 // UIList conatins the stateful widget's type and Rect which holds the widget
+// items can be narrowed down with a fuzzy filter, in which case the state refers to the filtered items
 #[derive(Clone)]
 pub struct UIList <'a> {
     name: String,
     items: Vec<String>,
+    filter: Option<String>,
+    filtered: Vec<FilteredItem>,
+    grouping: Option<Grouping>,
+    // rows displayed in the list, the state indexes these
+    rows: Vec<ListRow>,
+    // columns displayed next to the items, e.g. partitions & messages of a topic
+    details: HashMap<String, String>,
+    // order of the items shown in the title
+    sort: Option<String>,
     list: List<'a>,
     state: ListState,
     area: Rect,
+    focused: bool,
+    theme: Theme,
 }
 
-impl <'a> UIList <'a> {
-    pub fn new(name: String, items: Vec<String>) -> UIList<'a>{ 
-        let items_clone = items.clone();
-        let list_items = get_list_items(items_clone);
+// Item matching the filter along with the indices of matched chars
+#[derive(Clone)]
+struct FilteredItem {
+    idx: usize,
+    matched_indices: Vec<usize>,
+}
+
+// Grouped view of the list, items sharing a prefix up to a separator are folded into a group
+#[derive(Clone)]
+struct Grouping {
+    separators: Vec<char>,
+    // prefixes of the expanded groups, e.g. team.domain.
+    expanded: HashSet<String>,
+}
 
-        let list_count = list_items.len();
-        let name = format!("{} ({})", name, list_count);
+impl Grouping {
+    // End of the next segment of the name after the given offset, including the separator
+    // leading separators belong to the segment, e.g. __consumer_
+    fn prefix_end(&self, name: &str, start: usize) -> Option<usize> {
+        let rest = &name[start..];
+        let segment_start = rest.find(|c| !self.separators.contains(&c))?;
+        rest[segment_start..].find(|c| self.separators.contains(&c))
+            .map(|idx| start + segment_start + idx + 1)
+    }
+}
 
-        UIList {
-            name: name.clone(),
+// Row displayed in the list, the label of a row starts after the prefix of its group
+#[derive(Clone)]
+enum ListRow {
+    Item { item: FilteredItem, depth: usize, label_start: usize },
+    Group { prefix: String, depth: usize, label_start: usize, count: usize },
+}
+
+impl <'a> UIList <'a> {
+    pub fn new(name: String, items: Vec<String>, theme: Theme) -> UIList<'a>{ 
+        let mut list = UIList {
+            name,
             items,
-            list: get_list(name, list_items),
+            filter: None,
+            filtered: vec![],
+            grouping: None,
+            rows: vec![],
+            details: HashMap::new(),
+            sort: None,
+            list: List::default(),
             state: ListState::default(),
             area: Rect::default(),
-        }
+            focused: false,
+            theme,
+        };
+
+        list.apply_filter();
+        list
     }
 
     pub fn name(&self) -> &str {
-        // return base name w/o count
-        &self.name.split("(").collect::<Vec<&str>>()[0].trim()
+        &self.name
     }
 
-    pub fn update(&mut self, items: Vec<String>) {
-        let items_clone = items.clone();
-        let list_items = get_list_items(items_clone);
-        let list_count = list_items.len();
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
 
-        self.name = format!("{} ({})", self.name(), list_count);
+    // title with count of items, the order of the items and count of matches if filter is set
+    fn title(&self) -> String {
+        let sort = self.sort.as_ref().map(|s| format!(" by {}", s)).unwrap_or_default();
+        match &self.filter {
+            Some(filter) => format!("{} ({}){} /{} [{} matches]", self.name, self.items.len(), sort, filter, self.filtered.len()),
+            None => format!("{} ({}){}", self.name, self.items.len(), sort),
+        }
+    }
+
+    pub fn details(&self) -> &HashMap<String, String> {
+        &self.details
+    }
+
+    // set the columns displayed next to the items
+    pub fn set_details(&mut self, details: HashMap<String, String>) {
+        self.details = details;
+        self.build_rows();
+    }
+
+    // set the order shown in the title, the items are ordered by the caller
+    pub fn set_sort(&mut self, sort: Option<String>) {
+        if self.sort != sort {
+            self.sort = sort;
+            self.build_rows();
+        }
+    }
+
+    // update items, keeping the selected item selected if it is still available
+    pub fn update(&mut self, items: Vec<String>) {
+        let selected_item = self.selected_item();
         self.items = items;
-        self.list = get_list(self.name.clone(), list_items);
-        self.state = ListState::default();
+        self.apply_filter();
+        self.select_item(selected_item);
     }
     
     pub fn select(&mut self, idx: Option<usize>) {
@@ -168,8 +278,8 @@ impl <'a> UIList <'a> {
 
     pub fn selected_item(&self) -> Option<String> {
         if let Some(idx) = self.state() {
-            if let Some(item) = self.items.get(idx) {
-                return Some(item.clone());
+            if let Some(ListRow::Item { item, .. }) = self.rows.get(idx) {
+                return self.items.get(item.idx).cloned();
             }
         }
 
@@ -180,6 +290,23 @@ impl <'a> UIList <'a> {
         self.state.selected()
     }
 
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_deref()
+    }
+
+    // set the filter, keeping the selected item selected if it still matches
+    // otherwise the best match gets selected
+    pub fn set_filter(&mut self, filter: Option<String>) {
+        let selected_item = self.selected_item();
+        self.filter = filter;
+        self.apply_filter();
+        self.select_item(selected_item);
+
+        if self.state().is_none() && !self.filtered.is_empty() {
+            self.state.select(Some(0));
+        }
+    }
+
     pub fn handle_navigation(&mut self, direction: Direction) {
         match direction {
             Direction::UP => self.handle_up(),
@@ -188,9 +315,36 @@ impl <'a> UIList <'a> {
         }
     }
 
+    // select the item at the clicked row, returns true if an item got selected
+    pub fn handle_click(&mut self, position: Position) -> bool {
+        // first & last rows are the borders
+        let row = position.y.saturating_sub(self.area.y) as usize;
+        if row == 0 || row >= self.area.height.saturating_sub(1) as usize {
+            return false;
+        }
+
+        let idx = self.state.offset() + row - 1;
+        if idx >= self.rows.len() {
+            return false;
+        }
+
+        self.state.select(Some(idx));
+
+        // clicking a group expands or collapses it
+        if matches!(self.rows[idx], ListRow::Group { .. }) {
+            self.toggle_group();
+        }
+        true
+    }
+
     pub fn handle_down(&mut self) {
+        if self.rows.is_empty() {
+            self.state.select(None);
+            return;
+        }
+
         if let Some(idx) = self.state.selected() {
-            if idx == self.list.len()-1 {
+            if idx >= self.rows.len()-1 {
                 self.state.select(Some(0));
             } else {
                 self.state.select(Some(idx + 1));
@@ -201,9 +355,14 @@ impl <'a> UIList <'a> {
     }
 
     pub fn handle_up(&mut self) {
+        if self.rows.is_empty() {
+            self.state.select(None);
+            return;
+        }
+
         if let Some(idx) = self.state.selected() {
-            if idx == 0 {
-                self.state.select(Some(self.list.len()-1));
+            if idx == 0 || idx > self.rows.len()-1 {
+                self.state.select(Some(self.rows.len()-1));
             } else {
                 self.state.select(Some(idx - 1));
             }
@@ -211,20 +370,205 @@ impl <'a> UIList <'a> {
             self.state.select(Some(0))
         }
     }
+
+    // select the item with the given value, the filter is cleared if it hides the item
+    // returns false if the item is not found
+    pub fn select_value(&mut self, value: &str) -> bool {
+        if !self.items.iter().any(|i| i == value) {
+            return false;
+        }
+
+        if self.filtered.iter().all(|f| self.items[f.idx] != value) {
+            self.set_filter(None);
+        }
+
+        self.select_item(Some(value.to_string()));
+        true
+    }
+
+    // fold the items into groups by the given separators, None lists the items flat
+    pub fn set_grouping(&mut self, separators: Option<&[char]>) {
+        let selected_item = self.selected_item();
+        self.grouping = separators.map(|s| Grouping { separators: s.to_vec(), expanded: HashSet::new() });
+        self.build_rows();
+        self.select_item(selected_item);
+    }
+
+    pub fn is_grouped(&self) -> bool {
+        self.grouping.is_some()
+    }
+
+    // expand or collapse the selected group, returns false if no group is selected
+    pub fn toggle_group(&mut self) -> bool {
+        let Some(ListRow::Group { prefix, .. }) = self.state().and_then(|idx| self.rows.get(idx)) else {
+            return false;
+        };
+        let prefix = prefix.clone();
+
+        if let Some(grouping) = &mut self.grouping && !grouping.expanded.remove(&prefix) {
+            grouping.expanded.insert(prefix.clone());
+        }
+
+        self.build_rows();
+        let idx = self.rows.iter().position(|r| matches!(r, ListRow::Group { prefix: p, .. } if *p == prefix));
+        self.state.select(idx);
+        true
+    }
+
+    // whether the items are displayed as a tree, the filtered items are always listed flat
+    fn shows_groups(&self) -> bool {
+        self.grouping.is_some() && self.filter.as_deref().unwrap_or_default().is_empty()
+    }
+
+    // select the given item if it is available in the filtered items
+    // the groups of the item are expanded so that it stays visible
+    fn select_item(&mut self, item: Option<String>) {
+        if let Some(item) = &item && self.shows_groups() && let Some(grouping) = &mut self.grouping {
+            let mut expanded = false;
+            let mut start = 0;
+            while let Some(end) = grouping.prefix_end(item, start) {
+                expanded |= grouping.expanded.insert(item[..end].to_string());
+                start = end;
+            }
+
+            if expanded {
+                self.build_rows();
+            }
+        }
+
+        let idx = item.and_then(|item| self.rows.iter()
+            .position(|r| matches!(r, ListRow::Item { item: f, .. } if self.items[f.idx] == item)));
+        self.state.select(idx);
+    }
+
+    // filter items with fuzzy matching, best matches first
+    // and rebuild the list widget
+    fn apply_filter(&mut self) {
+        self.filtered = match &self.filter {
+            Some(filter) if !filter.is_empty() => {
+                let matcher = SkimMatcherV2::default().smart_case();
+                let mut matches = self.items.iter().enumerate()
+                    .filter_map(|(idx, item)| matcher.fuzzy_indices(item, filter)
+                        .map(|(score, matched_indices)| (score, FilteredItem { idx, matched_indices })))
+                    .collect::<Vec<(i64, FilteredItem)>>();
+
+                matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.idx.cmp(&b.1.idx)));
+                matches.into_iter().map(|(_, f)| f).collect()
+            },
+            _ => (0..self.items.len())
+                .map(|idx| FilteredItem { idx, matched_indices: vec![] })
+                .collect(),
+        };
+
+        self.build_rows();
+    }
+
+    // rebuild the rows & the list widget
+    fn build_rows(&mut self) {
+        self.rows = match &self.grouping {
+            Some(grouping) if self.shows_groups() => group_rows(&self.items, grouping),
+            _ => self.filtered.iter()
+                .map(|f| ListRow::Item { item: f.clone(), depth: 0, label_start: 0 })
+                .collect(),
+        };
+
+        let expanded = self.grouping.as_ref().filter(|_| self.shows_groups()).map(|g| &g.expanded);
+        let list_items = get_list_items(&self.items, &self.rows, expanded, &self.details, &self.theme);
+        self.list = get_list(self.title(), list_items, &self.theme, self.focused);
+    }
 }
 
-fn get_list_items(items: Vec<String>) -> Vec<ListItem<'static>> {
-    items
-        .into_iter()
-        .map(|i| ListItem::new(vec![text::Line::from(Span::raw(i))]))
-        //.map(|i| ListItem::new(Paragraph::new(i).wrap(Wrap { trim: false })))
+// rows of the grouped view, sorted so that the items sharing a prefix are next to each other
+fn group_rows(items: &[String], grouping: &Grouping) -> Vec<ListRow> {
+    let mut idxs = (0..items.len()).collect::<Vec<usize>>();
+    idxs.sort_by(|a, b| items[*a].cmp(&items[*b]));
+
+    let mut rows = vec![];
+    push_group_rows(items, &idxs, 0, 0, grouping, &mut rows);
+    rows
+}
+
+// rows of the items sharing the prefix up to the given offset, a prefix of a single item is not folded
+fn push_group_rows(items: &[String], idxs: &[usize], start: usize, depth: usize, grouping: &Grouping, rows: &mut Vec<ListRow>) {
+    let mut i = 0;
+    while i < idxs.len() {
+        let name = &items[idxs[i]];
+        let group = grouping.prefix_end(name, start)
+            .map(|end| &name[..end])
+            .map(|prefix| (prefix, idxs[i..].iter().take_while(|j| items[**j].starts_with(prefix)).count()))
+            .filter(|(_, count)| *count > 1);
+
+        match group {
+            Some((prefix, count)) => {
+                rows.push(ListRow::Group { prefix: prefix.to_string(), depth, label_start: start, count });
+                if grouping.expanded.contains(prefix) {
+                    push_group_rows(items, &idxs[i..i + count], prefix.len(), depth + 1, grouping, rows);
+                }
+                i += count;
+            },
+            None => {
+                rows.push(ListRow::Item { item: FilteredItem { idx: idxs[i], matched_indices: vec![] }, depth, label_start: start });
+                i += 1;
+            },
+        }
+    }
+}
+
+// list items with the matched chars highlighted, groups are shown along with their expanded state
+// in the grouped view
+fn get_list_items(items: &[String], rows: &[ListRow], expanded: Option<&HashSet<String>>, details: &HashMap<String, String>, theme: &Theme) -> Vec<ListItem<'static>> {
+    // details are aligned after the longest item
+    let details_offset = items.iter()
+        .filter(|i| details.contains_key(*i))
+        .map(|i| i.chars().count())
+        .max()
+        .unwrap_or_default()
+        .min(MAX_DETAILS_OFFSET);
+
+    rows.iter()
+        .map(|row| {
+            let (idx, mut spans) = match row {
+                ListRow::Group { prefix, depth, label_start, count } => {
+                    let symbol = if expanded.is_some_and(|e| e.contains(prefix)) { "▾" } else { "▸" };
+                    let label = &prefix[*label_start..prefix.len() - 1];
+                    let line = format!("{}{} {} ({})", "  ".repeat(*depth), symbol, label, count);
+                    return ListItem::new(vec![text::Line::from(Span::raw(line).fg(theme.label))]);
+                },
+                ListRow::Item { item, depth, label_start } if expanded.is_some() => {
+                    // items without a label of their own are shown with the full name, e.g. team. along with team.orders
+                    let label = Some(&items[item.idx][*label_start..]).filter(|l| !l.is_empty()).unwrap_or(&items[item.idx]);
+                    (item.idx, vec![Span::raw(format!("{}  {}", "  ".repeat(*depth), label))])
+                },
+                ListRow::Item { item, .. } if item.matched_indices.is_empty() => (item.idx, vec![Span::raw(items[item.idx].clone())]),
+                ListRow::Item { item, .. } => {
+                    let spans = items[item.idx].chars().enumerate()
+                        .map(|(i, c)| {
+                            if item.matched_indices.contains(&i) {
+                                Span::styled(c.to_string(), theme.match_style())
+                            } else {
+                                Span::raw(c.to_string())
+                            }
+                        })
+                        .collect::<Vec<Span>>();
+                    (item.idx, spans)
+                },
+            };
+
+            if let Some(detail) = details.get(&items[idx]) {
+                let width = spans.iter().map(|s| s.content.chars().count()).sum::<usize>();
+                spans.push(Span::raw(" ".repeat(details_offset.saturating_sub(width) + 2)));
+                spans.push(Span::raw(detail.clone()).fg(theme.text));
+            }
+
+            ListItem::new(vec![text::Line::from(spans)])
+        })
         .collect::<Vec<ListItem>>()
 }
 
-fn get_list<'a>(name: String, list_items: Vec<ListItem<'a>>) -> List<'a> {
+fn get_list<'a>(name: String, list_items: Vec<ListItem<'a>>, theme: &Theme, focused: bool) -> List<'a> {
     List::new(list_items)
-        .block(create_block(NORMAL_COLOR, name, true))
-        .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(HIGHLIGHT_COLOR))
+        .block(create_block(theme, focused, name, true))
+        .highlight_style(theme.selection_style())
         .highlight_symbol("> ")
 }
 
@@ -236,12 +580,18 @@ impl <'a> AppWidget for UIList<'a> {
     }
     
     fn highlight_border(&mut self) {
-        self.list = self.list.clone().block(create_block(HIGHLIGHT_COLOR, self.name.clone(), true));
+        self.focused = true;
+        self.list = self.list.clone().block(create_block(&self.theme, self.focused, self.title(), true));
     }
 
     fn normalise_border(&mut self) {
-        self.list = self.list.clone().block(create_block(NORMAL_COLOR, self.name.clone(), true));
+        self.focused = false;
+        self.list = self.list.clone().block(create_block(&self.theme, self.focused, self.title(), true));
     } 
+
+    fn area(&self) -> Rect {
+        self.area
+    }
 }
 
 #[derive(Clone)]
@@ -251,15 +601,20 @@ pub struct UIParagraphWithScrollbar<'a> {
 }
 
 impl <'a> UIParagraphWithScrollbar<'a> {
-    pub fn new(name: String, text: Text<'a>, orientation: ScrollbarOrientation) -> UIParagraphWithScrollbar<'a> {
+    pub fn new(name: String, text: Text<'a>, orientation: ScrollbarOrientation, theme: Theme) -> UIParagraphWithScrollbar<'a> {
         let content_length = text.lines.len();
 
         UIParagraphWithScrollbar {
-            paragraph: UIParagraph::new(name, text),
+            paragraph: UIParagraph::new(name, text, theme),
             scrollbar: UIScrollbar::new(orientation, content_length),
         }
     }
 
+    pub fn without_wrap(mut self) -> UIParagraphWithScrollbar<'a> {
+        self.paragraph = self.paragraph.without_wrap();
+        self
+    }
+
     pub fn update(&mut self, text: Text<'a>) {
         let content_length = text.lines.len();
         self.paragraph.update(text); 
@@ -281,6 +636,23 @@ impl <'a> UIParagraphWithScrollbar<'a> {
         self.scrollbar.handle_up();
         self.paragraph.scroll((self.scrollbar.scroll_state,0));
     }
+
+    // scroll to the last lines of the content which fit in the given number of lines
+    pub fn scroll_to_end(&mut self, visible_lines: u16) {
+        self.scrollbar.scroll_to_end(visible_lines.into());
+        self.paragraph.scroll((self.scrollbar.scroll_state,0));
+    }
+
+    // whether the position lies on the scrollbar
+    pub fn scrollbar_contains(&self, position: Position) -> bool {
+        self.scrollbar.contains(position)
+    }
+
+    // scroll to the position on the scrollbar
+    pub fn handle_scrollbar_click(&mut self, position: Position) {
+        self.scrollbar.scroll_to(position);
+        self.paragraph.scroll((self.scrollbar.scroll_state,0));
+    }
 }
 
 impl <'a> AppWidget for UIParagraphWithScrollbar<'a> {
@@ -296,33 +668,43 @@ impl <'a> AppWidget for UIParagraphWithScrollbar<'a> {
     fn highlight_border(&mut self) {
         self.paragraph.highlight_border();
     }
+
+    fn area(&self) -> Rect {
+        self.paragraph.area()
+    }
 }
 
 // UiParagraph
 #[derive(Clone)]
 pub struct UIParagraph <'a> {
     name: String,
+    // title currently displayed, the name unless updated with another title
+    title: String,
+    focused: bool,
     paragraph: Paragraph<'a>,
     area: Rect,
+    theme: Theme,
+    wrap: bool,
 }
 
 impl <'a> UIParagraph<'a> {
-    pub fn new(name: String, text: Text<'a>) -> UIParagraph<'a> {
+    pub fn new(name: String, text: Text<'a>, theme: Theme) -> UIParagraph<'a> {
         UIParagraph {
             name: name.clone(),
+            title: name.clone(),
+            focused: false,
             paragraph: Paragraph::new(text)
-            .block(create_block(NORMAL_COLOR, name, true)),
-            area: Rect::default()
+            .block(create_block(&theme, false, name, true)),
+            area: Rect::default(),
+            theme,
+            wrap: true,
         }
     }
 
-    pub fn new_with_color(name: String, bg_color: Color, text: Text<'a>) -> UIParagraph<'a> {
-        UIParagraph {
-            name: name.clone(),
-            paragraph: Paragraph::new(text)
-            .block(create_block(bg_color, name, true)),
-            area: Rect::default()
-        }
+    // lines longer than the width are cut off instead of being wrapped
+    pub fn without_wrap(mut self) -> UIParagraph<'a> {
+        self.wrap = false;
+        self
     }
 
     pub fn update(&mut self, text: Text<'a>) {
@@ -330,9 +712,15 @@ impl <'a> UIParagraph<'a> {
     }
 
     pub fn update_with_name(&mut self, name: String, text: Text<'a>) {
-        self.paragraph = Paragraph::new(text)
-            .wrap(Wrap { trim: false })
-            .block(create_block(NORMAL_COLOR, name, true))
+        self.title = name;
+        let paragraph = Paragraph::new(text)
+            .block(create_block(&self.theme, self.focused, self.title.clone(), true));
+
+        self.paragraph = if self.wrap {
+            paragraph.wrap(Wrap { trim: false })
+        } else {
+            paragraph
+        };
     }
 
     pub fn scroll(&mut self, offset: (u16, u16)) {
@@ -347,20 +735,26 @@ impl <'a> AppWidget for UIParagraph<'a> {
     }
 
     fn normalise_border(&mut self) {
-        self.paragraph = self.paragraph.clone().block(create_block(NORMAL_COLOR, self.name.clone(), true));
+        self.focused = false;
+        self.paragraph = self.paragraph.clone().block(create_block(&self.theme, self.focused, self.title.clone(), true));
     }
 
     fn highlight_border(&mut self) {
-        self.paragraph = self.paragraph.clone().block(create_block(HIGHLIGHT_COLOR, self.name.clone(), true));
+        self.focused = true;
+        self.paragraph = self.paragraph.clone().block(create_block(&self.theme, self.focused, self.title.clone(), true));
+    }
+
+    fn area(&self) -> Rect {
+        self.area
     }
 }
 
-fn create_block<'a>(color: Color, name: String, with_border: bool) -> Block<'a> {
+fn create_block<'a>(theme: &Theme, focused: bool, name: String, with_border: bool) -> Block<'a> {
     let block = Block::default();
     if with_border {
         return block.borders(Borders::ALL)
             .border_set(symbols::border::ROUNDED)
-            .border_style(Style::new().fg(color)).title(name);
+            .border_style(theme.border_style(focused)).title(name);
     }
 
     block
@@ -378,13 +772,15 @@ pub enum InputEvent {
 pub struct UIInput<'a> {
     paragraph: UIParagraph<'a>,
     input: Input,
+    hint: Option<String>,
 }
 
 impl <'a> UIInput<'a> {
-    pub fn new(name: String) -> UIInput<'a> {
+    pub fn new(name: String, theme: Theme) -> UIInput<'a> {
         UIInput {
-            paragraph: UIParagraph::new(name, "".into()),
+            paragraph: UIParagraph::new(name, "".into(), theme),
             input: Input::default(),
+            hint: None,
         }
     }
 
@@ -400,17 +796,33 @@ impl <'a> UIInput<'a> {
 
     fn reset(&mut self) {
         self.input.reset();
-        self.paragraph.update("".into());
+        self.hint = None;
+        self.refresh();
     }
 
     fn enter_char(&mut self, new_char: char) {
         self.input.handle(InputRequest::InsertChar(new_char));
-        self.paragraph.update(self.input.value().to_string().into());
+        self.refresh();
     }
 
     fn remove_previous_char(&mut self) {
         self.input.handle(InputRequest::DeletePrevChar);
-        self.paragraph.update(self.input.value().to_string().into());
+        self.refresh();
+    }
+
+    // hint is displayed dimmed after the value
+    pub fn set_hint(&mut self, hint: Option<String>) {
+        self.hint = hint;
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        let mut spans = vec![Span::raw(self.input.value().to_string())];
+        if let Some(hint) = &self.hint {
+            spans.push(Span::from(hint.clone()).fg(self.paragraph.theme.text).dim());
+        }
+
+        self.paragraph.update(text::Line::from(spans).into());
     }
 
     fn move_cursor(&mut self, direction: Direction) {
@@ -425,9 +837,9 @@ impl <'a> UIInput<'a> {
         self.input.value().to_string()
     }
 
-    pub fn set_value(&mut self, value: &'a str) {
+    pub fn set_value(&mut self, value: &str) {
         self.input = self.input.clone().with_value(value.to_string());
-        self.paragraph.update(value.into());
+        self.refresh();
     }
 }
 
@@ -443,29 +855,63 @@ impl <'a> AppWidget for UIInput<'a> {
     fn highlight_border(&mut self) {
         self.paragraph.highlight_border();
     }
+
+    fn area(&self) -> Rect {
+        self.paragraph.area()
+    }
 }
 
 #[derive(Clone)]
 pub struct UIScrollbar<'a> {
     scrollbar: Scrollbar<'a>,
+    orientation: ScrollbarOrientation,
     area: Rect,
     state: ScrollbarState,
     scroll_state: u16,
+    content_length: usize,
 }
 
 impl <'a> UIScrollbar<'a> {
     pub fn new(orientation: ScrollbarOrientation, content_length: usize) -> UIScrollbar<'a> {
         UIScrollbar { 
-            scrollbar: Scrollbar::new(orientation),
+            scrollbar: Scrollbar::new(orientation.clone()),
+            orientation,
             area: Rect::default(), 
             state: ScrollbarState::new(content_length),
             scroll_state: 0,
+            content_length,
         }
     }
 
     pub fn update(&mut self, content_length: usize) {
         self.state = ScrollbarState::new(content_length);
         self.scroll_state = 0;
+        self.content_length = content_length;
+    }
+
+    // scroll to the content position proportional to the position on the track
+    // track excludes the begin & end symbols of the scrollbar
+    pub fn scroll_to(&mut self, position: Position) {
+        let track = self.track();
+        let track_length = track.height.saturating_sub(2).max(1);
+        let track_position = position.y.saturating_sub(track.y + 1).min(track_length - 1);
+        let max_position = self.content_length.saturating_sub(1);
+
+        self.scroll_state = (track_position as usize * max_position / (track_length as usize - 1).max(1)) as u16;
+        self.state = self.state.position(self.scroll_state.into());
+    }
+
+    pub fn scroll_to_end(&mut self, visible_lines: usize) {
+        self.scroll_state = self.content_length.saturating_sub(visible_lines) as u16;
+        self.state = self.state.position(self.scroll_state.into());
+    }
+
+    // area occupied by the vertical scrollbar
+    fn track(&self) -> Rect {
+        match self.orientation {
+            ScrollbarOrientation::VerticalLeft => Rect::new(self.area.x, self.area.y, 1, self.area.height),
+            _ => Rect::new(self.area.right().saturating_sub(1), self.area.y, 1, self.area.height),
+        }
     }
 
     pub fn handle_down(&mut self) {
@@ -490,6 +936,14 @@ impl <'a> AppWidget for UIScrollbar<'a> {
 
     fn highlight_border(&mut self) {
     }
+
+    fn area(&self) -> Rect {
+        self.area
+    }
+
+    fn contains(&self, position: Position) -> bool {
+        self.track().contains(position)
+    }
 }
 
 #[derive(Clone)]
@@ -504,7 +958,7 @@ pub struct UITable<'a> {
 const ROW_HEIGHT: u16 = 5;
 
 impl <'a> UITable<'a> {
-    pub fn new(columns: Vec<&'a str>, column_widths: Vec<u16>, data: Vec<Vec<String>>) -> UITable<'a> {
+    pub fn new(columns: Vec<&'a str>, column_widths: Vec<u16>, data: Vec<Vec<String>>, theme: Theme) -> UITable<'a> {
         let mut constraints = vec![];
         for column_width in column_widths {
             constraints.push(Constraint::Percentage(column_width))
@@ -518,7 +972,7 @@ impl <'a> UITable<'a> {
         UITable {
             table: Table::new(rows, constraints)
                 .header(Row::new(columns.clone()).bold())
-                .block(create_block(NORMAL_COLOR, "".to_string(), true)),
+                .block(create_block(&theme, false, "".to_string(), true)),
             area: Rect::default(),
             state: TableState::default(),
             columns,
@@ -564,4 +1018,8 @@ impl <'a> AppWidget for UITable<'a> {
 
     fn highlight_border(&mut self) {
     }
+
+    fn area(&self) -> Rect {
+        self.area
+    }
 }